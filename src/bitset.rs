@@ -0,0 +1,133 @@
+use crate::cell::Cell;
+
+// Line indices are u8, so no line can be longer than 255 cells; four 64-bit words are always enough.
+const BITSET_WORDS: usize = 4;
+
+/// A fixed-width set of cell positions, one bit per cell, with bit `i` representing cell `i` of a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitset {
+    words: [u64; BITSET_WORDS],
+}
+
+impl Bitset {
+    pub fn new() -> Bitset {
+        Bitset::default()
+    }
+
+    /// A bitset with the first `length` bits set.
+    pub fn filled(length: u8) -> Bitset {
+        let mut bitset = Bitset::new();
+        bitset.set_range(0, length);
+        bitset
+    }
+
+    pub fn get(&self, index: u8) -> bool {
+        let index = index as usize;
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: u8) {
+        let index = index as usize;
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn set_range(&mut self, start: u8, count: u8) {
+        for index in start..start + count {
+            self.set(index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// The number of bits set.
+    pub fn count(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+
+    pub fn and(&self, other: &Bitset) -> Bitset {
+        let mut result = *self;
+        for (word, other_word) in result.words.iter_mut().zip(other.words.iter()) {
+            *word &= other_word;
+        }
+        result
+    }
+
+    pub fn or(&self, other: &Bitset) -> Bitset {
+        let mut result = *self;
+        for (word, other_word) in result.words.iter_mut().zip(other.words.iter()) {
+            *word |= other_word;
+        }
+        result
+    }
+
+    pub fn xor(&self, other: &Bitset) -> Bitset {
+        let mut result = *self;
+        for (word, other_word) in result.words.iter_mut().zip(other.words.iter()) {
+            *word ^= other_word;
+        }
+        result
+    }
+
+    /// The bits set in `self` but not in `other`.
+    pub fn and_not(&self, other: &Bitset) -> Bitset {
+        let mut result = *self;
+        for (word, other_word) in result.words.iter_mut().zip(other.words.iter()) {
+            *word &= !other_word;
+        }
+        result
+    }
+
+    /// Moves every bit `count` positions towards the end of the line.
+    pub fn shifted(&self, count: u8) -> Bitset {
+        let word_shift = count as usize / 64;
+        let bit_shift = count as usize % 64;
+        let mut result = Bitset::new();
+        for i in (word_shift..BITSET_WORDS).rev() {
+            let source = i - word_shift;
+            let mut word = self.words[source] << bit_shift;
+            if bit_shift > 0 && source > 0 {
+                word |= self.words[source - 1] >> (64 - bit_shift);
+            }
+            result.words[i] = word;
+        }
+        result
+    }
+
+    /// Interprets the bitset as a line arrangement, with set bits as blocks and clear bits as spaces.
+    pub fn to_cells(self, length: u8) -> Vec<Cell> {
+        (0..length)
+            .map(|i| if self.get(i) { Cell::Block } else { Cell::Space })
+            .collect()
+    }
+}
+
+/// The known cells of a line, as a mask of which cells are known and which of those are blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KnownCells {
+    pub known: Bitset,
+    pub blocks: Bitset,
+}
+
+impl KnownCells {
+    pub fn from_cells(cells: &[Cell]) -> KnownCells {
+        let mut known_cells = KnownCells::default();
+        for (i, cell) in cells.iter().enumerate() {
+            match cell {
+                Cell::Block => {
+                    known_cells.known.set(i as u8);
+                    known_cells.blocks.set(i as u8);
+                }
+                Cell::Space => known_cells.known.set(i as u8),
+                Cell::Unknown => (),
+            }
+        }
+        known_cells
+    }
+
+    /// Whether the arrangement agrees with every known cell.
+    pub fn fits(&self, arrangement: &Bitset) -> bool {
+        arrangement.xor(&self.blocks).and(&self.known).is_empty()
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Tells a running solver to stop, either because `cancel` was called (e.g. from a Ctrl-C handler)
+/// or because its deadline has passed. Clones share the same cancelled flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn with_timeout(timeout: Duration) -> CancellationToken {
+        CancellationToken {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: Some(Instant::now() + timeout),
+        }
+    }
+
+    /// A token which is cancelled along with this one, but which also expires after `timeout`.
+    pub fn limited_to(&self, timeout: Duration) -> CancellationToken {
+        CancellationToken {
+            cancelled: Arc::clone(&self.cancelled),
+            deadline: Some(Instant::now() + timeout),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        match self.deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        }
+    }
+}
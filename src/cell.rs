@@ -1,9 +1,14 @@
+use serde::{Deserialize, Serialize};
+
 const CELL_DISPLAY_UNKNOWN: char = '?';
 const CELL_DISPLAY_BLOCK: char = '◼';
 const CELL_DISPLAY_SPACE: char = '∙';
 const CELL_DISPLAY_PADDING: &str = " ";
+const CELL_TEXT_UNKNOWN: char = '?';
+const CELL_TEXT_BLOCK: char = '#';
+const CELL_TEXT_SPACE: char = '.';
 
-#[derive(Clone,Debug,PartialEq, Eq)]
+#[derive(Clone,Debug,PartialEq, Eq, Serialize, Deserialize)]
 pub enum Cell {
     Block,
     Space,
@@ -18,17 +23,37 @@ impl Cell {
             Cell::Unknown => String::from(CELL_DISPLAY_UNKNOWN) + CELL_DISPLAY_PADDING,
         }
     }
-}
 
-pub fn generate_cell_vector(cell: Cell, count: u8) -> Vec<Cell> {
-    let mut cell_vector = Vec::new();
-    for _ in 0..count {
-        cell_vector.push(cell.clone());
+    /// The plain text form of the cell: `#` for a block, `.` for a space and `?` when unknown.
+    pub fn to_char(&self) -> char {
+        match self {
+            Cell::Block => CELL_TEXT_BLOCK,
+            Cell::Space => CELL_TEXT_SPACE,
+            Cell::Unknown => CELL_TEXT_UNKNOWN,
+        }
     }
-    cell_vector
+
+    pub fn from_char(c: char) -> Option<Cell> {
+        match c {
+            CELL_TEXT_BLOCK => Some(Cell::Block),
+            CELL_TEXT_SPACE => Some(Cell::Space),
+            CELL_TEXT_UNKNOWN => Some(Cell::Unknown),
+            _ => None,
+        }
+    }
+}
+
+/// The plain text form of a line of cells, as used in puzzle files and machine readable output.
+pub fn cells_to_text(cells: &[Cell]) -> String {
+    cells.iter().map(|cell| cell.to_char()).collect()
+}
+
+/// Reads a line of cells from its plain text form, or `None` if the text has any other characters.
+pub fn cells_from_text(text: &str) -> Option<Vec<Cell>> {
+    text.chars().map(Cell::from_char).collect()
 }
 
-pub fn display_cell_vector(cell_vector: &Vec<Cell>) -> String {
+pub(crate) fn display_cell_vector(cell_vector: &Vec<Cell>) -> String {
     let mut cells_display = String::new();
     for cell in cell_vector {
         cells_display += &cell.display();
@@ -36,7 +61,7 @@ pub fn display_cell_vector(cell_vector: &Vec<Cell>) -> String {
     cells_display
 }
 
-pub fn cell_vector_contains_unknown(cell_vector: &Vec<Cell>) -> bool {
+pub(crate) fn cell_vector_contains_unknown(cell_vector: &Vec<Cell>) -> bool {
     for cell in cell_vector {
         if *cell == Cell::Unknown {
             return true;
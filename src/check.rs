@@ -0,0 +1,76 @@
+use crate::bitset::KnownCells;
+use crate::cell::Cell;
+use crate::error::{PuzzleError, Result};
+use crate::line::{Line, LineType};
+use crate::line_algorithms::{clue_from_cells, generate_all_potential_solutions_for_clue};
+use crate::puzzle::Puzzle;
+use serde::Serialize;
+
+/// A row or column of a completed grid whose cells don't produce its clue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineMismatch {
+    /// The line's label, e.g. `Row 3`.
+    pub line: String,
+    pub expected: Vec<u8>,
+    pub actual: Vec<u8>,
+    /// The row and column of each cell which would have to change for the line to fit its clue, in the
+    /// arrangement of the clue which needs the fewest changes.
+    pub cells: Vec<(u8, u8)>,
+}
+
+impl Puzzle {
+    /// Checks a completed grid, given one row at a time, against every row and column clue. Any grid which
+    /// fits all the clues passes, even if the puzzle has other solutions. Returns the lines which don't fit,
+    /// rows first.
+    pub fn check_grid(&self, grid: &[Vec<Cell>]) -> Result<Vec<LineMismatch>> {
+        let col_count = self.col_count() as usize;
+        if grid.len() != self.row_count() as usize || grid.iter().any(|row| row.len() != col_count) {
+            return Err(PuzzleError::InvalidGrid {
+                message: format!("the grid must have {} rows of {col_count} cells", self.row_count()),
+            });
+        }
+        if grid.iter().flatten().any(|cell| *cell == Cell::Unknown) {
+            return Err(PuzzleError::InvalidGrid {
+                message: String::from("the grid must be complete, with no unknown cells"),
+            });
+        }
+        let mut mismatches = Vec::new();
+        for line in self.rows.iter().chain(self.cols.iter()) {
+            let cells: Vec<Cell> = match line.axis {
+                LineType::Row => grid[line.index as usize].clone(),
+                LineType::Col => grid.iter().map(|row| row[line.index as usize].clone()).collect(),
+            };
+            let actual = clue_from_cells(&cells);
+            if actual != line.clue {
+                mismatches.push(LineMismatch {
+                    line: line.label(),
+                    expected: line.clue.clone(),
+                    cells: offending_cells(line, &cells),
+                    actual,
+                });
+            }
+        }
+        Ok(mismatches)
+    }
+}
+
+/// The cells which differ from the arrangement of the line's clue closest to the given cells.
+fn offending_cells(line: &Line, cells: &[Cell]) -> Vec<(u8, u8)> {
+    let blocks = KnownCells::from_cells(cells).blocks;
+    let closest = generate_all_potential_solutions_for_clue(line.clue.clone(), cells.len() as i16)
+        .into_iter()
+        .min_by_key(|arrangement| arrangement.xor(&blocks).count());
+    match closest {
+        Some(arrangement) => {
+            let differences = arrangement.xor(&blocks);
+            (0..cells.len() as u8)
+                .filter(|&i| differences.get(i))
+                .map(|i| match line.axis {
+                    LineType::Row => (line.index, i),
+                    LineType::Col => (i, line.index),
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    }
+}
@@ -1,17 +1,495 @@
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use nonorust::{CancellationToken, ColorChoice, Colour, PuzzleFormat, RenderOptions, SolveOptions, Theme};
+use std::time::Duration;
+
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  Success (for commands which solve a puzzle: puzzle solved)
+  1  Puzzle not solved within the limits, or not without guessing
+  2  Invalid command line
+  3  Invalid puzzle, or a puzzle with no solution
+  4  Puzzle has more than one solution (see --check-uniqueness)
+  5  Puzzle file, or another input such as a grid to check, couldn't be read or parsed
+  6  Output couldn't be written
+  7  The solver's result differs from the solution given in the puzzle file
+
+Running nonorust with solve options but no command, e.g. `nonorust --path FILE`, is the same as `nonorust solve`.";
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-pub struct Args {
-    /// Puzzle file path
+#[command(
+    version,
+    about,
+    long_about = None,
+    after_help = EXIT_CODES_HELP,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Puzzle file path, or - to read the puzzle from standard input (for running without a command, as `solve`)
     #[arg(short, long)]
-    pub path: String,
+    pub path: Option<String>,
+
+    #[command(flatten)]
+    pub solver: SolverArgs,
+
+    #[command(flatten)]
+    pub global: GlobalArgs,
+}
+
+impl Cli {
+    /// The command to run, treating a command line without one as `solve`.
+    pub fn into_command(self) -> Command {
+        match (self.command, self.path) {
+            (Some(command), _) => command,
+            (None, Some(path)) => Command::Solve(SolveArgs {
+                path: Some(path),
+                resume: None,
+                save_state: None,
+                show_order: false,
+                solver: self.solver,
+            }),
+            (None, None) => Cli::command()
+                .error(ErrorKind::MissingRequiredArgument, "a command or --path is required")
+                .exit(),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve a puzzle and show the solution
+    Solve(SolveArgs),
+    /// Check that puzzle files can be loaded and that their clues are consistent
+    Validate(ValidateArgs),
+    /// Convert a puzzle file between the YAML and nonogram-db (.non) formats
+    Convert(ConvertArgs),
+    /// Generate a random puzzle
+    Generate(GenerateArgs),
+    /// Show the next cells which can be worked out, and why
+    Hint(HintArgs),
+    /// Play a puzzle in the terminal
+    Play(PlayArgs),
+    /// Solve many puzzles and summarise the results
+    Batch(BatchArgs),
+    /// Show a puzzle's details and how hard it is for the solver
+    Info(InfoArgs),
+    /// Check that a completed grid fits every row and column clue
+    Check(CheckArgs),
+    /// Turn, flip or invert a puzzle
+    Transform(TransformArgs),
+    /// Export a puzzle as an image, for printing or sharing
+    Export(ExportArgs),
+    /// Solve a puzzle until the solver stalls, then show how likely each unknown cell is to be a block
+    HeatMap(HeatMapArgs),
+}
+
+/// Options shared by every command.
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    /// Enable debug logging
+    #[arg(short, long, global = true, default_value_t = false)]
+    pub debug: bool,
+
+    /// Output format for reports
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// When to colour the output (auto colours only a terminal, and honours NO_COLOR)
+    #[arg(long, global = true, value_enum, default_value_t = ColorMode::Auto)]
+    pub color: ColorMode,
+
+    /// Glyphs used to draw the grid
+    #[arg(long, global = true, value_enum, default_value_t = GridTheme::Unicode)]
+    pub theme: GridTheme,
+
+    /// Draw grids as a compact preview, two rows to a line, without clues
+    #[arg(long, global = true, default_value_t = false)]
+    pub compact: bool,
+}
+
+impl GlobalArgs {
+    pub fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            compact: self.compact,
+            ..RenderOptions::new(self.theme.into(), self.color.into())
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl From<ColorMode> for ColorChoice {
+    fn from(color_mode: ColorMode) -> Self {
+        match color_mode {
+            ColorMode::Auto => ColorChoice::Auto,
+            ColorMode::Always => ColorChoice::Always,
+            ColorMode::Never => ColorChoice::Never,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridTheme {
+    /// ◼ for blocks, ∙ for spaces and ? for unknown cells
+    Unicode,
+    /// # for blocks, . for spaces and ? for unknown cells
+    Ascii,
+    /// Blocks two characters wide
+    DoubleWidth,
+}
+
+impl From<GridTheme> for Theme {
+    fn from(grid_theme: GridTheme) -> Self {
+        match grid_theme {
+            GridTheme::Unicode => Theme::Unicode,
+            GridTheme::Ascii => Theme::Ascii,
+            GridTheme::DoubleWidth => Theme::DoubleWidth,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text
+    Text,
+    /// JSON, for other programs
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// The YAML format used by the files in `data`
+    Yaml,
+    /// The nonogram-db format
+    Non,
+}
+
+impl From<FileFormat> for PuzzleFormat {
+    fn from(file_format: FileFormat) -> Self {
+        match file_format {
+            FileFormat::Yaml => PuzzleFormat::Yaml,
+            FileFormat::Non => PuzzleFormat::Non,
+        }
+    }
+}
 
+/// Options controlling how hard the solver tries.
+#[derive(Args, Debug, Clone)]
+pub struct SolverArgs {
     /// Maximum iterations before giving up
     #[arg(short, long, default_value_t = 100)]
     pub max_iterations: u32,
 
-    /// Enable debug logging
-    #[arg(short, long, default_value_t = false)]
-    pub debug: bool,
-}
\ No newline at end of file
+    /// Maximum time in seconds to spend solving before stopping and showing the partial solution
+    #[arg(long)]
+    pub timeout: Option<u64>,
+
+    /// Number of threads used to solve lines concurrently (0 = one per CPU core)
+    #[arg(short, long, default_value_t = 1)]
+    pub threads: usize,
+
+    /// Solve with line logic only, without probing cells when line logic stalls
+    #[arg(long, default_value_t = false)]
+    pub no_probing: bool,
+
+    /// If the puzzle can't be solved without guessing, search for every solution to check that it is unique
+    #[arg(long, default_value_t = false)]
+    pub check_uniqueness: bool,
+}
+
+impl SolverArgs {
+    /// The solver options for one puzzle. Its timeout starts now, and it is also cancelled along with `cancellation`.
+    pub fn solve_options(&self, cancellation: &CancellationToken) -> SolveOptions {
+        let cancellation = match self.timeout {
+            Some(timeout) => cancellation.limited_to(Duration::from_secs(timeout)),
+            None => cancellation.clone(),
+        };
+        SolveOptions {
+            max_iterations: self.max_iterations,
+            threads: self.threads,
+            probing: !self.no_probing,
+            cancellation,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct SolveArgs {
+    /// Puzzle file path, or - to read the puzzle from standard input
+    #[arg(short, long, required_unless_present = "resume", conflicts_with = "resume")]
+    pub path: Option<String>,
+
+    /// Carry on solving from a state saved with --save-state
+    #[arg(long)]
+    pub resume: Option<String>,
+
+    /// Save the solver state to this file when solving stops, so that it can be carried on with --resume
+    #[arg(long)]
+    pub save_state: Option<String>,
+
+    /// Also show the order in which cells were decided, shading early cells light and late cells dark
+    #[arg(long, default_value_t = false)]
+    pub show_order: bool,
+
+    #[command(flatten)]
+    pub solver: SolverArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Puzzle file paths, or - to read a puzzle from standard input
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Puzzle file to convert, or - to read a YAML puzzle from standard input
+    pub input: String,
+
+    /// File to write the converted puzzle to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Format to convert to (defaults to the output file's extension, or YAML)
+    #[arg(long, value_enum)]
+    pub to: Option<FileFormat>,
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    /// Number of columns
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(1..))]
+    pub width: u8,
+
+    /// Number of rows
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(1..))]
+    pub height: u8,
+
+    /// Fraction of cells which are blocks, between 0 and 1
+    #[arg(long, default_value_t = 0.5, value_parser = parse_density)]
+    pub density: f64,
+
+    /// Seed for the random number generator, to generate the same puzzle again
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Title of the generated puzzle
+    #[arg(long, default_value = "Random puzzle")]
+    pub title: String,
+
+    /// Keep generating until the puzzle has a unique solution
+    #[arg(long, default_value_t = false)]
+    pub unique: bool,
+
+    /// Maximum number of puzzles to try when --unique is given
+    #[arg(long, default_value_t = 100)]
+    pub attempts: u32,
+
+    /// File to write the puzzle to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Format to write (defaults to the output file's extension, or YAML)
+    #[arg(long, value_enum)]
+    pub to: Option<FileFormat>,
+}
+
+#[derive(Args, Debug)]
+pub struct HintArgs {
+    /// Puzzle file path, or - to read the puzzle from standard input
+    #[arg(short, long)]
+    pub path: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PlayArgs {
+    /// Puzzle file path
+    #[arg(short, long, required_unless_present = "resume", conflicts_with = "resume")]
+    pub path: Option<String>,
+
+    /// Carry on with a game saved with the `w` command
+    #[arg(long)]
+    pub resume: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Puzzle files, or directories which are searched for .yaml and .non puzzle files
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+
+    /// Show a compact preview of each puzzle's grid after its result
+    #[arg(long, default_value_t = false)]
+    pub thumbnails: bool,
+
+    #[command(flatten)]
+    pub solver: SolverArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// Puzzle file path, or - to read the puzzle from standard input
+    #[arg(short, long)]
+    pub path: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Puzzle file path
+    #[arg(short, long)]
+    pub path: String,
+
+    /// The completed grid, using # for blocks and . for spaces: either a file with one row per line, - to read
+    /// it from standard input, or the grid itself with its rows separated by /
+    pub grid: String,
+}
+
+#[derive(Args, Debug)]
+pub struct TransformArgs {
+    /// Puzzle file to transform, or - to read a YAML puzzle from standard input
+    pub input: String,
+
+    /// How to transform the puzzle
+    #[arg(long, value_enum)]
+    pub transform: Transformation,
+
+    /// File to write the transformed puzzle to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Format to write (defaults to the output file's extension, or YAML)
+    #[arg(long, value_enum)]
+    pub to: Option<FileFormat>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transformation {
+    /// Swap rows and columns
+    Transpose,
+    /// Turn a quarter turn clockwise
+    Rotate90,
+    /// Turn upside down
+    Rotate180,
+    /// Turn a quarter turn anticlockwise
+    Rotate270,
+    /// Swap left and right
+    MirrorHorizontal,
+    /// Swap top and bottom
+    MirrorVertical,
+    /// Swap blocks and spaces (needs the puzzle's solution, or a puzzle the solver can solve)
+    Invert,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Puzzle file path, or - to read the puzzle from standard input
+    #[arg(short, long, required_unless_present = "resume", conflicts_with = "resume")]
+    pub path: Option<String>,
+
+    /// Export the partly solved puzzle from a state saved with `solve --save-state`
+    #[arg(long)]
+    pub resume: Option<String>,
+
+    #[command(flatten)]
+    pub format: ExportFormat,
+
+    /// Draw the solved cells, from the puzzle file's solution or else from solving the puzzle
+    #[arg(long, default_value_t = false)]
+    pub solved: bool,
+
+    /// Width and height of each cell, in pixels
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    pub cell_size: u32,
+
+    /// PNG colour of blocks, as #rrggbb
+    #[arg(long, default_value = "#000000", value_parser = parse_colour)]
+    pub block_colour: Colour,
+
+    /// PNG colour of spaces, as #rrggbb
+    #[arg(long, default_value = "#ffffff", value_parser = parse_colour)]
+    pub space_colour: Colour,
+
+    /// PNG colour of cells which aren't solved yet, as #rrggbb
+    #[arg(long, default_value = "#c0c0c0", value_parser = parse_colour)]
+    pub unknown_colour: Colour,
+
+    /// Draw lines around every cell of a PNG, optionally in the given colour
+    #[arg(long, num_args = 0..=1, default_missing_value = "#808080", value_parser = parse_colour)]
+    pub grid_lines: Option<Colour>,
+
+    /// File to write to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub solver: SolverArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct HeatMapArgs {
+    /// Puzzle file path, or - to read the puzzle from standard input
+    #[arg(short, long, required_unless_present = "resume", conflicts_with = "resume")]
+    pub path: Option<String>,
+
+    /// Carry on from a partly solved puzzle, from a state saved with `solve --save-state`
+    #[arg(long)]
+    pub resume: Option<String>,
+
+    /// Write the row and column fractions for each cell as CSV, instead of drawing the heat map
+    #[arg(long, default_value_t = false)]
+    pub csv: bool,
+
+    /// File to write to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub solver: SolverArgs,
+}
+
+/// The format to export a puzzle in.
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct ExportFormat {
+    /// Export as an SVG image, for printing
+    #[arg(long)]
+    pub svg: bool,
+
+    /// Export the cells as a PNG image, with one square per cell
+    #[arg(long)]
+    pub png: bool,
+
+    /// Export as a standalone web page, on which the puzzle can be played and checked
+    #[arg(long)]
+    pub html: bool,
+}
+
+/// Parses a density for `--density`, which must be between 0 and 1.
+fn parse_density(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(String::from("must be a number between 0 and 1")),
+    }
+}
+
+/// Parses a colour written as `#rrggbb`.
+fn parse_colour(value: &str) -> Result<Colour, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(String::from("must be a colour written as #rrggbb"));
+    }
+    let mut colour = [0; 3];
+    for (i, component) in colour.iter_mut().enumerate() {
+        *component = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| String::from("must be a colour written as #rrggbb"))?;
+    }
+    Ok(colour)
+}
@@ -0,0 +1,120 @@
+use crate::cli::{BatchArgs, OutputFormat};
+use crate::commands::{cancel_on_ctrl_c, load_puzzle, print_json, solve_puzzle};
+use crate::exit_code::Outcome;
+use nonorust::RenderOptions;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct BatchJson {
+    path: String,
+    #[serde(skip)]
+    exit_outcome: Outcome,
+    rows: Option<u8>,
+    columns: Option<u8>,
+    outcome: &'static str,
+    message: Option<String>,
+    iterations: u32,
+    seconds: f64,
+    #[serde(skip)]
+    thumbnail: Option<String>,
+}
+
+pub fn run(args: &BatchArgs, format: OutputFormat, render_options: &RenderOptions) -> Outcome {
+    let mut paths = Vec::new();
+    for path in &args.paths {
+        find_puzzle_files(Path::new(path), &mut paths);
+    }
+    let cancellation = cancel_on_ctrl_c();
+    let mut reports = Vec::new();
+    for path in &paths {
+        if cancellation.is_cancelled() {
+            log::warn!("Batch cancelled - skipping the remaining puzzles");
+            break;
+        }
+        let report = match load_puzzle(path) {
+            Ok(mut puzzle) => {
+                let report = solve_puzzle(&mut puzzle, &args.solver, &cancellation);
+                let outcome = Outcome::from_result(&report.result);
+                BatchJson {
+                    path: path.clone(),
+                    exit_outcome: outcome,
+                    rows: Some(puzzle.row_count()),
+                    columns: Some(puzzle.col_count()),
+                    outcome: outcome.name(),
+                    message: report.result.as_ref().err().map(|err| err.to_string()),
+                    iterations: report.stats.iterations,
+                    seconds: report.duration.as_secs_f64(),
+                    thumbnail: args.thumbnails.then(|| puzzle.render_compact(render_options)),
+                }
+            }
+            Err(outcome) => BatchJson {
+                path: path.clone(),
+                exit_outcome: outcome,
+                rows: None,
+                columns: None,
+                outcome: outcome.name(),
+                message: None,
+                iterations: 0,
+                seconds: 0.0,
+                thumbnail: None,
+            },
+        };
+        if format == OutputFormat::Text {
+            print_report(&report);
+        }
+        reports.push(report);
+    }
+    match format {
+        OutputFormat::Text => print_summary(&reports),
+        OutputFormat::Json => print_json(&reports),
+    }
+    Outcome::worst(reports.iter().map(|report| report.exit_outcome))
+}
+
+/// Adds a puzzle file to `paths`, or every puzzle file under a directory, in name order.
+fn find_puzzle_files(path: &Path, paths: &mut Vec<String>) {
+    if !path.is_dir() {
+        paths.push(path.display().to_string());
+        return;
+    }
+    let mut entries: Vec<_> = match std::fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(err) => {
+            eprintln!("Error reading directory {}: {err}", path.display());
+            return;
+        }
+    };
+    entries.sort();
+    for entry in entries {
+        let is_puzzle_file = entry
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml" || extension == "non");
+        if entry.is_dir() || is_puzzle_file {
+            find_puzzle_files(&entry, paths);
+        }
+    }
+}
+
+fn print_report(report: &BatchJson) {
+    let size = match (report.rows, report.columns) {
+        (Some(rows), Some(columns)) => format!("{rows}x{columns}"),
+        _ => String::from("-"),
+    };
+    println!(
+        "{:<50} {:>7} {:<18} {:>4} iterations {:>8.3} s",
+        report.path, size, report.outcome, report.iterations, report.seconds
+    );
+    if let Some(thumbnail) = &report.thumbnail {
+        println!("{thumbnail}");
+    }
+}
+
+fn print_summary(reports: &[BatchJson]) {
+    let solved = reports
+        .iter()
+        .filter(|report| report.exit_outcome == Outcome::Solved)
+        .count();
+    let seconds: f64 = reports.iter().map(|report| report.seconds).sum();
+    println!("{solved} of {} puzzles solved in {seconds:.3} s", reports.len());
+}
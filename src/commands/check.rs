@@ -0,0 +1,89 @@
+use crate::cli::{CheckArgs, OutputFormat};
+use crate::commands::{load_puzzle, print_json};
+use crate::exit_code::Outcome;
+use nonorust::{Cell, LineMismatch, cells_from_text, clue_as_string};
+use serde::Serialize;
+use std::io::Read;
+
+#[derive(Serialize)]
+struct CheckJson {
+    path: String,
+    correct: bool,
+    mismatches: Vec<LineMismatch>,
+}
+
+pub fn run(args: &CheckArgs, format: OutputFormat) -> Outcome {
+    let puzzle = match load_puzzle(&args.path) {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    let grid = match read_grid(&args.grid) {
+        Ok(grid) => grid,
+        Err(message) => {
+            eprintln!("Error reading grid: {message}");
+            return Outcome::ReadError;
+        }
+    };
+    let mismatches = match puzzle.check_grid(&grid) {
+        Ok(mismatches) => mismatches,
+        Err(err) => {
+            eprintln!("Error checking grid: {err}");
+            return Outcome::from_error(&err);
+        }
+    };
+    match format {
+        OutputFormat::Text => print_text(&mismatches),
+        OutputFormat::Json => print_json(&CheckJson {
+            path: args.path.clone(),
+            correct: mismatches.is_empty(),
+            mismatches: mismatches.clone(),
+        }),
+    }
+    if mismatches.is_empty() { Outcome::Success } else { Outcome::NotSolved }
+}
+
+/// Reads a grid from a file, from standard input, or from the argument itself, with one row per line or `/`.
+fn read_grid(grid: &str) -> Result<Vec<Vec<Cell>>, String> {
+    let text = if grid == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|err| format!("couldn't read standard input: {err}"))?;
+        text
+    } else if std::path::Path::new(grid).is_file() {
+        std::fs::read_to_string(grid).map_err(|err| format!("couldn't read {grid}: {err}"))?
+    } else {
+        grid.to_string()
+    };
+    text.split(['\n', '/'])
+        .map(|row| row.trim())
+        .filter(|row| !row.is_empty())
+        .map(|row| cells_from_text(row).ok_or(format!("'{row}' may only contain '#' and '.'")))
+        .collect()
+}
+
+fn print_text(mismatches: &[LineMismatch]) {
+    if mismatches.is_empty() {
+        println!("The grid fits every clue - puzzle solved!");
+        return;
+    }
+    for mismatch in mismatches {
+        let cells: Vec<String> = mismatch
+            .cells
+            .iter()
+            .map(|(row, col)| format!("Row {}, Col {}", row + 1, col + 1))
+            .collect();
+        println!(
+            "{}: expected {}, but the cells give {} - check {}",
+            mismatch.line,
+            clue_or_none(&mismatch.expected),
+            clue_or_none(&mismatch.actual),
+            cells.join("; ")
+        );
+    }
+    println!("{} lines don't fit their clues", mismatches.len());
+}
+
+fn clue_or_none(clue: &[u8]) -> String {
+    if clue.is_empty() { String::from("no blocks") } else { clue_as_string(clue) }
+}
@@ -0,0 +1,34 @@
+use crate::cli::{ConvertArgs, OutputFormat};
+use crate::commands::{PuzzleFileJson, print_json, write_puzzle_file};
+use crate::exit_code::Outcome;
+use nonorust::PuzzleConfig;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ConvertJson {
+    input: String,
+    #[serde(flatten)]
+    written: PuzzleFileJson,
+}
+
+pub fn run(args: &ConvertArgs, format: OutputFormat) -> Outcome {
+    let puzzle_config = match PuzzleConfig::read(&args.input) {
+        Ok(puzzle_config) => puzzle_config,
+        Err(err) => {
+            eprintln!("Error loading puzzle: {err}");
+            return Outcome::from_error(&err);
+        }
+    };
+    match write_puzzle_file(&puzzle_config, args.to, args.output.as_deref(), format) {
+        Ok(written) => {
+            if format == OutputFormat::Json {
+                print_json(&ConvertJson {
+                    input: args.input.clone(),
+                    written,
+                });
+            }
+            Outcome::Success
+        }
+        Err(outcome) => outcome,
+    }
+}
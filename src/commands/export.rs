@@ -0,0 +1,81 @@
+use crate::cli::ExportArgs;
+use crate::commands::{cancel_on_ctrl_c, load_puzzle, resume_puzzle, solve_puzzle, write_output};
+use crate::exit_code::Outcome;
+use nonorust::{PngOptions, Puzzle, SvgOptions};
+
+pub fn run(args: &ExportArgs) -> Outcome {
+    let loaded = match (&args.path, &args.resume) {
+        (_, Some(state_path)) => resume_puzzle(state_path).map(|(puzzle, _)| puzzle),
+        (Some(path), None) => load_puzzle(path),
+        (None, None) => unreachable!("clap requires --path or --resume"),
+    };
+    let mut puzzle = match loaded {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    // Exporting a partly solved puzzle is still worth doing, but exits as the solver did.
+    let outcome = if args.solved { fill_in_solution(&mut puzzle, args) } else { Outcome::Success };
+    let contents = if args.format.png {
+        let mut png = Vec::new();
+        let options = PngOptions {
+            cell_size: args.cell_size,
+            block_colour: args.block_colour,
+            space_colour: args.space_colour,
+            unknown_colour: args.unknown_colour,
+            grid_line_colour: args.grid_lines,
+        };
+        if let Err(err) = puzzle.write_png(&mut png, &options) {
+            eprintln!("Error drawing PNG: {err}");
+            return Outcome::WriteError;
+        }
+        png
+    } else if args.format.html {
+        if puzzle.solution.is_none() {
+            find_solution(&mut puzzle, args);
+        }
+        match puzzle.to_html() {
+            Ok(html) => html.into_bytes(),
+            Err(err) => {
+                eprintln!("Error exporting HTML: {err}");
+                return Outcome::NotSolved;
+            }
+        }
+    } else {
+        let options = SvgOptions {
+            cell_size: args.cell_size,
+            show_cells: args.solved || args.resume.is_some(),
+        };
+        puzzle.to_svg(&options).into_bytes()
+    };
+    match write_output(args.output.as_deref(), contents) {
+        Ok(()) => outcome,
+        Err(outcome) => outcome,
+    }
+}
+
+/// Solves a copy of the puzzle to find the solution which the HTML page checks the player's cells against.
+fn find_solution(puzzle: &mut Puzzle, args: &ExportArgs) {
+    let mut solved = puzzle.clone();
+    let report = solve_puzzle(&mut solved, &args.solver, &cancel_on_ctrl_c());
+    if report.result.is_ok() {
+        puzzle.solution = Some(solved.grid());
+    }
+}
+
+/// Sets every cell to the puzzle file's solution, or else to as much of the solution as the solver can find.
+/// Returns how solving went.
+fn fill_in_solution(puzzle: &mut Puzzle, args: &ExportArgs) -> Outcome {
+    if let Some(solution) = puzzle.solution.clone() {
+        for (row, cells) in solution.into_iter().enumerate() {
+            for (col, cell) in cells.into_iter().enumerate() {
+                puzzle.set_cell(row as u8, col as u8, cell);
+            }
+        }
+        return Outcome::Solved;
+    }
+    let report = solve_puzzle(puzzle, &args.solver, &cancel_on_ctrl_c());
+    if let Err(err) = &report.result {
+        eprintln!("Warning: exporting a partly solved puzzle, since {err}");
+    }
+    Outcome::from_result(&report.result)
+}
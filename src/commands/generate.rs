@@ -0,0 +1,77 @@
+use crate::cli::{GenerateArgs, OutputFormat};
+use crate::commands::{PuzzleFileJson, print_json, write_puzzle_file};
+use crate::exit_code::Outcome;
+use nonorust::{Cell, PuzzleConfig, SolveOptions, cells_to_text, clues_from_grid};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct GenerateJson {
+    seed: u64,
+    attempts: u32,
+    #[serde(flatten)]
+    written: PuzzleFileJson,
+}
+
+pub fn run(args: &GenerateArgs, format: OutputFormat) -> Outcome {
+    let seed = args.seed.unwrap_or_else(|| fastrand::u64(..));
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let attempts = if args.unique { args.attempts } else { 1 };
+    for attempt in 1..=attempts {
+        let grid: Vec<Vec<Cell>> = (0..args.height)
+            .map(|_| {
+                (0..args.width)
+                    .map(|_| if rng.f64() < args.density { Cell::Block } else { Cell::Space })
+                    .collect()
+            })
+            .collect();
+        let (rows, columns) = clues_from_grid(&grid);
+        let puzzle_config = PuzzleConfig {
+            title: args.title.clone(),
+            by: None,
+            license: None,
+            source: Some(format!(
+                "nonorust generate --width {} --height {} --density {} --seed {seed}{}",
+                args.width,
+                args.height,
+                args.density,
+                if args.unique { " --unique" } else { "" }
+            )),
+            rows,
+            columns,
+            givens: None,
+            // Only a unique solution is sure to be the one the solver finds.
+            solution: args
+                .unique
+                .then(|| grid.iter().map(|row| cells_to_text(row)).collect()),
+        };
+        if args.unique && !has_unique_solution(&puzzle_config) {
+            log::debug!("Generated puzzle {attempt} doesn't have a unique solution, trying again");
+            continue;
+        }
+        return match write_puzzle_file(&puzzle_config, args.to, args.output.as_deref(), format) {
+            Ok(written) => {
+                if format == OutputFormat::Json {
+                    print_json(&GenerateJson {
+                        seed,
+                        attempts: attempt,
+                        written,
+                    });
+                }
+                Outcome::Success
+            }
+            Err(outcome) => outcome,
+        };
+    }
+    eprintln!("Couldn't generate a puzzle with a unique solution in {attempts} attempts");
+    Outcome::MultipleSolutions
+}
+
+fn has_unique_solution(puzzle_config: &PuzzleConfig) -> bool {
+    match puzzle_config.clone().into_puzzle() {
+        Ok(puzzle) => {
+            let (solutions, exhaustive) = puzzle.find_solutions(2, &SolveOptions::default());
+            solutions.len() == 1 && exhaustive
+        }
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,36 @@
+use crate::cli::HeatMapArgs;
+use crate::commands::{cancel_on_ctrl_c, load_puzzle, resume_puzzle, solve_puzzle, write_output};
+use crate::exit_code::Outcome;
+use nonorust::RenderOptions;
+
+pub fn run(args: &HeatMapArgs, render_options: &RenderOptions) -> Outcome {
+    let loaded = match (&args.path, &args.resume) {
+        (_, Some(state_path)) => resume_puzzle(state_path).map(|(puzzle, _)| puzzle),
+        (Some(path), None) => load_puzzle(path),
+        (None, None) => unreachable!("clap requires --path or --resume"),
+    };
+    let mut puzzle = match loaded {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    // The heat map shows where the solver got stuck, so solve as far as line logic and probing can go first.
+    let report = solve_puzzle(&mut puzzle, &args.solver, &cancel_on_ctrl_c());
+    match &report.result {
+        Ok(()) => log::info!("The puzzle was solved, so every cell is known"),
+        Err(err) => log::info!("Solving stopped: {err}"),
+    }
+    let contents = if args.csv {
+        puzzle.heat_map_csv()
+    } else {
+        // Colour codes only make sense in the terminal, not in a file.
+        let options = RenderOptions {
+            color: render_options.color && args.output.is_none(),
+            ..*render_options
+        };
+        puzzle.render_heat_map(&options)
+    };
+    match write_output(args.output.as_deref(), contents) {
+        Ok(()) => Outcome::Success,
+        Err(outcome) => outcome,
+    }
+}
@@ -0,0 +1,40 @@
+use crate::cli::{HintArgs, OutputFormat};
+use crate::commands::{load_puzzle, print_json};
+use crate::exit_code::Outcome;
+use nonorust::Cell;
+
+pub fn run(args: &HintArgs, format: OutputFormat) -> Outcome {
+    let puzzle = match load_puzzle(&args.path) {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    let hint = puzzle.hint();
+    match format {
+        OutputFormat::Text => match &hint {
+            Some(hint) => {
+                match &hint.line {
+                    Some(line) => println!("{line} decides {} cells:", hint.cells.len()),
+                    None => println!("Probing decides a cell:"),
+                }
+                for (row, col, cell) in &hint.cells {
+                    println!("  Row {}, Col {}: {}", row + 1, col + 1, cell_name(cell));
+                }
+            }
+            None => println!("No cells can be worked out without guessing"),
+        },
+        OutputFormat::Json => print_json(&hint),
+    }
+    if hint.is_some() {
+        Outcome::Success
+    } else {
+        Outcome::NotSolved
+    }
+}
+
+pub fn cell_name(cell: &Cell) -> &'static str {
+    match cell {
+        Cell::Block => "block",
+        Cell::Space => "space",
+        Cell::Unknown => "unknown",
+    }
+}
@@ -0,0 +1,63 @@
+use crate::cli::{InfoArgs, OutputFormat};
+use crate::commands::{load_puzzle, print_json};
+use crate::exit_code::Outcome;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct InfoJson {
+    title: String,
+    author: String,
+    license: String,
+    source: String,
+    rows: u8,
+    columns: u8,
+    blocks: u32,
+    potential_solutions: u128,
+    lines_with_one_arrangement: usize,
+}
+
+pub fn run(args: &InfoArgs, format: OutputFormat) -> Outcome {
+    let puzzle = match load_puzzle(&args.path) {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    let lines = puzzle.rows().iter().chain(puzzle.cols());
+    let info = InfoJson {
+        title: puzzle.title.clone(),
+        author: puzzle.author.clone(),
+        license: puzzle.license.clone(),
+        source: puzzle.source.clone(),
+        rows: puzzle.row_count(),
+        columns: puzzle.col_count(),
+        blocks: puzzle.rows().iter().flat_map(|row| &row.clue).map(|&n| n as u32).sum(),
+        potential_solutions: lines
+            .clone()
+            .map(|line| line.calculate_all_potential_solutions())
+            .fold(0, u128::saturating_add),
+        lines_with_one_arrangement: lines
+            .filter(|line| line.calculate_all_potential_solutions() == 1)
+            .count(),
+    };
+    match format {
+        OutputFormat::Text => print_text(&info),
+        OutputFormat::Json => print_json(&info),
+    }
+    Outcome::Success
+}
+
+fn print_text(info: &InfoJson) {
+    println!("Title:   {}", info.title);
+    for (name, value) in [("Author:", &info.author), ("License:", &info.license), ("Source:", &info.source)] {
+        if !value.is_empty() {
+            println!("{name:<8} {value}");
+        }
+    }
+    println!("Size:    {} rows x {} columns", info.rows, info.columns);
+    println!("Blocks:  {}", info.blocks);
+    println!("Arrangements of all lines: {}", info.potential_solutions);
+    println!(
+        "Lines with only one arrangement: {} of {}",
+        info.lines_with_one_arrangement,
+        info.rows as usize + info.columns as usize
+    );
+}
@@ -0,0 +1,206 @@
+pub mod batch;
+pub mod check;
+pub mod convert;
+pub mod export;
+pub mod generate;
+pub mod heat_map;
+pub mod hint;
+pub mod info;
+pub mod play;
+pub mod solve;
+pub mod transform;
+pub mod validate;
+
+use crate::cli::{FileFormat, OutputFormat, SolverArgs};
+use crate::exit_code::Outcome;
+use nonorust::{
+    CancellationToken, CellMismatch, LoggingObserver, Puzzle, PuzzleConfig, PuzzleError, PuzzleFormat, SolveObserver,
+    SolveOptions, SolveStats, SolverState, cells_to_text,
+};
+use serde::Serialize;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Loads a puzzle, reporting any error on stderr.
+pub fn load_puzzle(path: &str) -> Result<Puzzle, Outcome> {
+    PuzzleConfig::build(path.to_string()).map_err(report_load_error)
+}
+
+/// Loads a saved solver state, reporting any error on stderr.
+pub fn load_state(path: &str) -> Result<SolverState, Outcome> {
+    SolverState::load(path).map_err(report_load_error)
+}
+
+/// Rebuilds a puzzle from a saved solver state, along with the iterations used before it was saved.
+pub fn resume_puzzle(path: &str) -> Result<(Puzzle, u32), Outcome> {
+    let state = load_state(path)?;
+    let iterations = state.iterations;
+    let puzzle = state.into_puzzle(path).map_err(report_load_error)?;
+    Ok((puzzle, iterations))
+}
+
+fn report_load_error(err: PuzzleError) -> Outcome {
+    eprintln!("Error loading puzzle: {err}");
+    Outcome::from_error(&err)
+}
+
+/// Saves a solver state, reporting any error on stderr.
+pub fn save_state(path: &str, state: &SolverState) -> Result<(), Outcome> {
+    state.save(path).map_err(|err| {
+        eprintln!("Error saving state: {err}");
+        Outcome::WriteError
+    })
+}
+
+/// A cancellation token which is cancelled by Ctrl-C. A second Ctrl-C quits immediately.
+pub fn cancel_on_ctrl_c() -> CancellationToken {
+    let cancellation = CancellationToken::new();
+    let ctrl_c_cancellation = cancellation.clone();
+    ctrlc::set_handler(move || {
+        if ctrl_c_cancellation.is_cancelled() {
+            std::process::exit(130);
+        }
+        ctrl_c_cancellation.cancel();
+    })
+    .expect("Failed to set Ctrl-C handler - quitting");
+    cancellation
+}
+
+pub fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(err) => eprintln!("Error writing JSON: {err}"),
+    }
+}
+
+/// The format to write a puzzle file in: the one asked for, or else the one the output file's extension suggests.
+fn output_format(to: Option<FileFormat>, output: Option<&str>) -> PuzzleFormat {
+    match (to, output) {
+        (Some(file_format), _) => file_format.into(),
+        (None, Some(output)) => PuzzleFormat::from_path(output),
+        (None, None) => PuzzleFormat::Yaml,
+    }
+}
+
+/// Writes text, or an image, to a file, or to standard output if there is no file, reporting any error on stderr.
+pub fn write_output(output: Option<&str>, contents: impl AsRef<[u8]>) -> Result<(), Outcome> {
+    let result = match output {
+        Some(output) => std::fs::write(output, contents),
+        None => std::io::stdout().write_all(contents.as_ref()),
+    };
+    result.map_err(|err| {
+        eprintln!("Error writing {}: {err}", output.unwrap_or("to standard output"));
+        Outcome::WriteError
+    })
+}
+
+/// Where, and in which format, a command wrote a puzzle file, for its JSON report.
+#[derive(Serialize)]
+pub struct PuzzleFileJson {
+    output: Option<String>,
+    format: &'static str,
+    /// The puzzle file itself, when it would otherwise have been written to standard output along with the report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    puzzle: Option<String>,
+}
+
+/// Writes a puzzle file to `output`, or to standard output if there is no file. For a JSON report the puzzle
+/// file is returned instead of being written to standard output, so that it can be included in the report.
+pub fn write_puzzle_file(
+    puzzle_config: &PuzzleConfig,
+    to: Option<FileFormat>,
+    output: Option<&str>,
+    format: OutputFormat,
+) -> Result<PuzzleFileJson, Outcome> {
+    let file_format = output_format(to, output);
+    let contents = puzzle_config.to_string_as(file_format).map_err(|err| {
+        eprintln!("Error writing puzzle: {err}");
+        Outcome::from_error(&err)
+    })?;
+    let puzzle = if output.is_none() && format == OutputFormat::Json {
+        Some(contents)
+    } else {
+        write_output(output, contents)?;
+        None
+    };
+    Ok(PuzzleFileJson {
+        output: output.map(str::to_string),
+        format: match file_format {
+            PuzzleFormat::Yaml => "yaml",
+            PuzzleFormat::Non => "non",
+        },
+        puzzle,
+    })
+}
+
+/// The result of solving one puzzle with the solver options from the command line.
+pub struct SolveReport {
+    pub stats: SolveStats,
+    pub result: Result<(), PuzzleError>,
+    pub duration: Duration,
+    /// Cells which differ from the solution given in the puzzle file.
+    pub mismatches: Vec<CellMismatch>,
+}
+
+pub fn solve_puzzle(puzzle: &mut Puzzle, solver: &SolverArgs, cancellation: &CancellationToken) -> SolveReport {
+    solve_puzzle_with_observer(puzzle, solver, cancellation, &mut LoggingObserver)
+}
+
+/// Solves a puzzle as `solve_puzzle` does, reporting the solver's progress to the given observer.
+pub fn solve_puzzle_with_observer(
+    puzzle: &mut Puzzle,
+    solver: &SolverArgs,
+    cancellation: &CancellationToken,
+    observer: &mut dyn SolveObserver,
+) -> SolveReport {
+    let started = Instant::now();
+    let solve_options = solver.solve_options(cancellation);
+    let stats = puzzle.solve_with_observer(&solve_options, observer);
+    let result = match stats.result() {
+        Err(PuzzleError::Unsolved { .. }) if solver.check_uniqueness => {
+            check_uniqueness(puzzle, &solve_options, stats.iterations)
+        }
+        result => result,
+    };
+    let duration = started.elapsed();
+    let mismatches = puzzle.solution_mismatches();
+    let result = if mismatches.is_empty() {
+        result
+    } else {
+        Err(PuzzleError::SolutionMismatch { cells: mismatches.len() })
+    };
+    SolveReport {
+        stats,
+        result,
+        duration,
+        mismatches,
+    }
+}
+
+/// Searches for a second solution of a puzzle which the solver couldn't finish without guessing.
+/// A unique solution found by the search replaces the partially solved puzzle.
+fn check_uniqueness(
+    puzzle: &mut Puzzle,
+    solve_options: &SolveOptions,
+    iterations: u32,
+) -> Result<(), PuzzleError> {
+    log::info!("Searching for solutions to check whether the puzzle has a unique solution");
+    let (mut solutions, exhaustive) = puzzle.find_solutions(2, solve_options);
+    match solutions.len() {
+        0 if exhaustive => Err(PuzzleError::NoSolution),
+        1 if exhaustive => {
+            *puzzle = solutions.remove(0);
+            Ok(())
+        }
+        2 => Err(PuzzleError::MultipleSolutions),
+        _ => Err(PuzzleError::LimitReached {
+            iterations,
+            cancelled: solve_options.cancellation.is_cancelled(),
+        }),
+    }
+}
+
+/// The grid as one string per row, using `#` for blocks, `.` for spaces and `?` for unknown cells.
+pub fn grid_as_text(puzzle: &Puzzle) -> Vec<String> {
+    puzzle.grid().iter().map(|row| cells_to_text(row)).collect()
+}
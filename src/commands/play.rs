@@ -0,0 +1,150 @@
+use crate::cli::PlayArgs;
+use crate::commands::hint::cell_name;
+use crate::commands::{load_puzzle, load_state, save_state};
+use crate::exit_code::Outcome;
+use nonorust::{Cell, Puzzle, PuzzleError, RenderOptions, SolveOptions, SolverState};
+use std::io::{BufRead, Write};
+
+const PLAY_HELP: &str = "Commands:
+  b ROW COL   mark a cell as a block
+  s ROW COL   mark a cell as a space
+  u ROW COL   clear a cell
+  h           show a hint
+  c           check the grid against the solution
+  w FILE      save the game, to carry on later with --resume FILE
+  q           quit";
+
+pub fn run(args: &PlayArgs, render_options: &RenderOptions) -> Outcome {
+    let loaded = match (&args.path, &args.resume) {
+        (_, Some(state_path)) => load_game(state_path),
+        (Some(path), None) => load_puzzle(path),
+        (None, None) => unreachable!("clap requires --path or --resume"),
+    };
+    let mut puzzle = match loaded {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    let solution = find_solution(&puzzle);
+    println!("{PLAY_HELP}");
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock().lines();
+    loop {
+        print!("{}", puzzle.render(render_options));
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        let Some(Ok(line)) = input.next() else {
+            return Outcome::NotSolved;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["b", row, col] => set_cell(&mut puzzle, row, col, Cell::Block),
+            ["s", row, col] => set_cell(&mut puzzle, row, col, Cell::Space),
+            ["u", row, col] => set_cell(&mut puzzle, row, col, Cell::Unknown),
+            ["h"] => show_hint(&puzzle),
+            ["c"] => {
+                if check(&puzzle, solution.as_ref(), render_options) {
+                    return Outcome::Solved;
+                }
+            }
+            ["w", state_path] => {
+                if save_state(state_path, &SolverState::new(&puzzle, 0)).is_ok() {
+                    println!("Game saved to {state_path}");
+                }
+            }
+            ["q"] => return Outcome::NotSolved,
+            _ => println!("{PLAY_HELP}"),
+        }
+    }
+}
+
+/// Loads a saved game. Unlike resuming the solver, the player's cells are kept even if they are wrong.
+fn load_game(state_path: &str) -> Result<Puzzle, Outcome> {
+    let state = load_state(state_path)?;
+    let loaded = state
+        .cells(state_path)
+        .and_then(|grid| Ok((state.puzzle.into_puzzle_from(state_path)?, grid)));
+    let (mut puzzle, grid) = loaded.map_err(|err: PuzzleError| {
+        eprintln!("Error loading game: {err}");
+        Outcome::from_error(&err)
+    })?;
+    for (row, cells) in grid.into_iter().enumerate() {
+        for (col, cell) in cells.into_iter().enumerate() {
+            puzzle.set_cell(row as u8, col as u8, cell);
+        }
+    }
+    Ok(puzzle)
+}
+
+/// Solves a copy of the puzzle to check the player's grid against, guessing if line logic isn't enough.
+fn find_solution(puzzle: &Puzzle) -> Option<Puzzle> {
+    let mut solution = puzzle.clone();
+    if solution.solve(&SolveOptions::default()).solved {
+        return Some(solution);
+    }
+    let (mut solutions, _) = puzzle.find_solutions(1, &SolveOptions::default());
+    solutions.pop()
+}
+
+fn set_cell(puzzle: &mut Puzzle, row: &str, col: &str, cell: Cell) {
+    match (parse_coordinate(row, puzzle.row_count()), parse_coordinate(col, puzzle.col_count())) {
+        (Some(row), Some(col)) => puzzle.set_cell(row, col, cell),
+        _ => println!(
+            "Rows are numbered 1 to {} and columns 1 to {}",
+            puzzle.row_count(),
+            puzzle.col_count()
+        ),
+    }
+}
+
+/// Converts a 1-based row or column number into an index.
+fn parse_coordinate(value: &str, count: u8) -> Option<u8> {
+    match value.parse::<u8>() {
+        Ok(number) if number >= 1 && number <= count => Some(number - 1),
+        _ => None,
+    }
+}
+
+fn show_hint(puzzle: &Puzzle) {
+    if puzzle.has_contradiction() {
+        println!("Some of your cells don't fit the clues - check your grid");
+        return;
+    }
+    match puzzle.hint() {
+        Some(hint) => {
+            let (row, col, cell) = &hint.cells[0];
+            match &hint.line {
+                Some(line) => println!("Look at {line}: Row {}, Col {} is a {}", row + 1, col + 1, cell_name(cell)),
+                None => println!("Try Row {}, Col {}: it can only be a {}", row + 1, col + 1, cell_name(cell)),
+            }
+        }
+        None => println!("No cells can be worked out without guessing"),
+    }
+}
+
+/// Reports how the player's grid compares with the solution. Returns true once the grid is complete and correct.
+fn check(puzzle: &Puzzle, solution: Option<&Puzzle>, render_options: &RenderOptions) -> bool {
+    let Some(solution) = solution else {
+        println!("The solver couldn't find a solution to check your grid against");
+        return false;
+    };
+    let mut mistakes = 0;
+    let mut unknown = 0;
+    for row in 0..puzzle.row_count() {
+        for col in 0..puzzle.col_count() {
+            match puzzle.cell(row, col) {
+                Cell::Unknown => unknown += 1,
+                cell if cell != solution.cell(row, col) => mistakes += 1,
+                _ => (),
+            }
+        }
+    }
+    if mistakes == 0 && unknown == 0 {
+        print!("{}", puzzle.render(render_options));
+        println!("Puzzle solved - well done!");
+        true
+    } else {
+        println!("{mistakes} cells are wrong, and {unknown} cells are still to do");
+        false
+    }
+}
@@ -0,0 +1,117 @@
+use crate::cli::{OutputFormat, SolveArgs};
+use crate::commands::{
+    SolveReport, cancel_on_ctrl_c, grid_as_text, load_puzzle, print_json, resume_puzzle, save_state,
+    solve_puzzle_with_observer,
+};
+use crate::commands::hint::cell_name;
+use crate::exit_code::Outcome;
+use nonorust::{CellDecision, CellMismatch, DecisionRecorder, Puzzle, PuzzleError, RenderOptions, SolverState};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct SolveJson {
+    path: String,
+    title: String,
+    outcome: &'static str,
+    message: Option<String>,
+    iterations: u32,
+    resumed_iterations: u32,
+    probes: u32,
+    probe_iterations: u32,
+    cells_fixed_by_probing: u32,
+    seconds: f64,
+    grid: Vec<String>,
+    solution_mismatches: Vec<CellMismatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decision_order: Option<Vec<Vec<Option<CellDecision>>>>,
+}
+
+pub fn run(args: &SolveArgs, format: OutputFormat, render_options: &RenderOptions) -> Outcome {
+    let (path, loaded) = match (&args.path, &args.resume) {
+        (_, Some(state_path)) => (state_path, resume_puzzle(state_path)),
+        (Some(path), None) => (path, load_puzzle(path).map(|puzzle| (puzzle, 0))),
+        (None, None) => unreachable!("clap requires --path or --resume"),
+    };
+    let (mut puzzle, resumed_iterations) = match loaded {
+        Ok(loaded) => loaded,
+        Err(outcome) => return outcome,
+    };
+    let cancellation = cancel_on_ctrl_c();
+    log::info!(
+        "Starting Nonogram solver with file: {} and max iterations set to: {}",
+        path,
+        args.solver.max_iterations
+    );
+    let mut recorder = DecisionRecorder::new(&puzzle);
+    let report = solve_puzzle_with_observer(&mut puzzle, &args.solver, &cancellation, &mut recorder);
+    let outcome = Outcome::from_result(&report.result);
+    match format {
+        OutputFormat::Text => {
+            print_text(&puzzle, &report, resumed_iterations, render_options);
+            if args.show_order {
+                print!("{}", puzzle.render_decision_order(recorder.decisions(), render_options));
+            }
+        }
+        OutputFormat::Json => print_json(&SolveJson {
+            path: path.clone(),
+            title: puzzle.title.clone(),
+            outcome: outcome.name(),
+            message: report.result.as_ref().err().map(|err| err.to_string()),
+            iterations: report.stats.iterations,
+            resumed_iterations,
+            probes: report.stats.probes,
+            probe_iterations: report.stats.probe_iterations,
+            cells_fixed_by_probing: report.stats.cells_fixed_by_probing,
+            seconds: report.duration.as_secs_f64(),
+            grid: grid_as_text(&puzzle),
+            solution_mismatches: report.mismatches.clone(),
+            decision_order: args.show_order.then(|| recorder.decisions().to_vec()),
+        }),
+    }
+    if let Some(state_path) = &args.save_state {
+        let state = SolverState::new(&puzzle, resumed_iterations + report.stats.iterations);
+        if let Err(outcome) = save_state(state_path, &state) {
+            return outcome;
+        }
+    }
+    outcome
+}
+
+fn print_text(puzzle: &Puzzle, report: &SolveReport, resumed_iterations: u32, render_options: &RenderOptions) {
+    print!("{}", puzzle.render(render_options));
+    match &report.result {
+        Ok(()) => println!("Puzzle solved!"),
+        Err(PuzzleError::LimitReached { cancelled: true, .. }) => println!(
+            "Puzzle NOT solved - stopped after {:.1} s",
+            report.duration.as_secs_f64()
+        ),
+        Err(PuzzleError::LimitReached { .. } | PuzzleError::Unsolved { .. }) => {
+            println!("Puzzle NOT solved!")
+        }
+        Err(err @ PuzzleError::SolutionMismatch { .. }) => println!("Puzzle solved, but {err}"),
+        Err(err) => println!("Puzzle NOT solved - {err}"),
+    }
+    for mismatch in &report.mismatches {
+        println!(
+            "  Row {}, Col {}: expected a {}, but found a {}",
+            mismatch.row + 1,
+            mismatch.col + 1,
+            cell_name(&mismatch.expected),
+            cell_name(&mismatch.actual)
+        );
+    }
+    if resumed_iterations > 0 {
+        println!(
+            "Iterations used: {} (plus {resumed_iterations} before resuming)",
+            report.stats.iterations
+        );
+    } else {
+        println!("Iterations used: {}", report.stats.iterations);
+    }
+    if report.stats.probes > 0 {
+        println!(
+            "Probes made: {} (using {} iterations), cells fixed by probing: {}",
+            report.stats.probes, report.stats.probe_iterations, report.stats.cells_fixed_by_probing
+        );
+    }
+}
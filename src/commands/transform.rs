@@ -0,0 +1,66 @@
+use crate::cli::{OutputFormat, TransformArgs, Transformation};
+use crate::commands::{PuzzleFileJson, load_puzzle, print_json, write_puzzle_file};
+use crate::exit_code::Outcome;
+use clap::ValueEnum;
+use nonorust::{Puzzle, PuzzleConfig, SolveOptions};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct TransformJson {
+    input: String,
+    transform: String,
+    #[serde(flatten)]
+    written: PuzzleFileJson,
+}
+
+pub fn run(args: &TransformArgs, format: OutputFormat) -> Outcome {
+    let puzzle = match load_puzzle(&args.input) {
+        Ok(puzzle) => puzzle,
+        Err(outcome) => return outcome,
+    };
+    let transformed = match args.transform {
+        Transformation::Transpose => puzzle.transpose(),
+        Transformation::Rotate90 => puzzle.rotate_90(),
+        Transformation::Rotate180 => puzzle.rotate_180(),
+        Transformation::Rotate270 => puzzle.rotate_270(),
+        Transformation::MirrorHorizontal => puzzle.mirror_horizontal(),
+        Transformation::MirrorVertical => puzzle.mirror_vertical(),
+        Transformation::Invert => invert(&puzzle),
+    };
+    let transformed = match transformed {
+        Ok(transformed) => transformed,
+        Err(err) => {
+            eprintln!("Error transforming puzzle: {err}");
+            return Outcome::from_error(&err);
+        }
+    };
+    let puzzle_config = PuzzleConfig::from(&transformed);
+    match write_puzzle_file(&puzzle_config, args.to, args.output.as_deref(), format) {
+        Ok(written) => {
+            if format == OutputFormat::Json {
+                print_json(&TransformJson {
+                    input: args.input.clone(),
+                    transform: args
+                        .transform
+                        .to_possible_value()
+                        .map_or_else(String::new, |value| value.get_name().to_string()),
+                    written,
+                });
+            }
+            Outcome::Success
+        }
+        Err(outcome) => outcome,
+    }
+}
+
+/// Inverts a puzzle, solving it first if its file doesn't give its solution.
+fn invert(puzzle: &Puzzle) -> nonorust::Result<Puzzle> {
+    if puzzle.solution.is_some() {
+        return puzzle.invert();
+    }
+    let mut solved = puzzle.clone();
+    solved.solve(&SolveOptions::default()).result()?;
+    // Rebuilt without the solved cells, so that the only cells known before solving are the givens.
+    let inverted = solved.invert()?;
+    PuzzleConfig::from(&inverted).into_puzzle()
+}
@@ -0,0 +1,44 @@
+use crate::cli::{OutputFormat, ValidateArgs};
+use crate::commands::print_json;
+use crate::exit_code::Outcome;
+use nonorust::PuzzleConfig;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ValidateJson {
+    path: String,
+    valid: bool,
+    message: String,
+}
+
+pub fn run(args: &ValidateArgs, format: OutputFormat) -> Outcome {
+    let mut outcomes = Vec::new();
+    let mut reports = Vec::new();
+    for path in &args.paths {
+        let (outcome, message) = match PuzzleConfig::build(path.clone()) {
+            Ok(puzzle) => (
+                Outcome::Success,
+                format!(
+                    "OK - \"{}\", {} rows x {} columns",
+                    puzzle.title,
+                    puzzle.row_count(),
+                    puzzle.col_count()
+                ),
+            ),
+            Err(err) => (Outcome::from_error(&err), err.to_string()),
+        };
+        if format == OutputFormat::Text {
+            println!("{path}: {message}");
+        }
+        reports.push(ValidateJson {
+            path: path.clone(),
+            valid: outcome == Outcome::Success,
+            message,
+        });
+        outcomes.push(outcome);
+    }
+    if format == OutputFormat::Json {
+        print_json(&reports);
+    }
+    Outcome::worst(outcomes)
+}
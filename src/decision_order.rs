@@ -0,0 +1,79 @@
+use crate::cell::Cell;
+use crate::line::{Line, LineType};
+use crate::observer::{LoggingObserver, SolveObserver};
+use crate::puzzle::{Puzzle, SolveStats};
+use serde::Serialize;
+
+/// When and how the solver decided a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CellDecision {
+    /// The iteration of line logic in which the cell was decided, or after which it was probed, counting from 1
+    /// across the whole solve.
+    pub iteration: u32,
+    /// Whether solving a row or a column decided the cell, or `None` if probing did.
+    pub axis: Option<LineType>,
+}
+
+/// Records which iteration, and which axis, decided each cell while a puzzle is solved, and logs progress as
+/// `LoggingObserver` does. Cells known before solving started, or filled in by a solution search, have no decision.
+pub struct DecisionRecorder {
+    iteration: u32,
+    decisions: Vec<Vec<Option<CellDecision>>>,
+}
+
+impl DecisionRecorder {
+    pub fn new(puzzle: &Puzzle) -> DecisionRecorder {
+        DecisionRecorder {
+            iteration: 0,
+            decisions: vec![vec![None; puzzle.col_count() as usize]; puzzle.row_count() as usize],
+        }
+    }
+
+    /// The decision for each cell, one row at a time.
+    pub fn decisions(&self) -> &[Vec<Option<CellDecision>>] {
+        &self.decisions
+    }
+
+    fn record(&mut self, row: u8, col: u8, axis: Option<LineType>) {
+        let decision = &mut self.decisions[row as usize][col as usize];
+        if decision.is_none() {
+            *decision = Some(CellDecision {
+                iteration: self.iteration,
+                axis,
+            });
+        }
+    }
+}
+
+impl SolveObserver for DecisionRecorder {
+    fn iteration_started(&mut self, iteration: u32, potential_solutions_remaining: u32) {
+        // Line logic counts its iterations afresh after each probe, so the recorder keeps its own count.
+        self.iteration += 1;
+        LoggingObserver.iteration_started(iteration, potential_solutions_remaining);
+    }
+
+    fn line_solved(&mut self, line: &Line, cells_changed: &[u8]) {
+        for &i in cells_changed {
+            match line.axis {
+                LineType::Row => self.record(line.index, i, Some(LineType::Row)),
+                LineType::Col => self.record(i, line.index, Some(LineType::Col)),
+            }
+        }
+        LoggingObserver.line_solved(line, cells_changed);
+    }
+
+    fn cell_probed(&mut self, row: u8, col: u8, guess: &Cell, led_to_contradiction: bool) {
+        if led_to_contradiction {
+            self.record(row, col, None);
+        }
+        LoggingObserver.cell_probed(row, col, guess, led_to_contradiction);
+    }
+
+    fn contradiction(&mut self, line: &Line) {
+        LoggingObserver.contradiction(line);
+    }
+
+    fn completed(&mut self, stats: &SolveStats) {
+        LoggingObserver.completed(stats);
+    }
+}
@@ -0,0 +1,71 @@
+use thiserror::Error;
+
+/// Used in place of a file path when reporting errors in puzzles which didn't come from a file, such as
+/// those built in code, loaded from a string or reader, or transformed.
+pub(crate) const IN_MEMORY_SOURCE: &str = "<in memory>";
+
+/// Everything that can go wrong while loading or solving a puzzle.
+#[derive(Debug, Error)]
+pub enum PuzzleError {
+    #[error("couldn't read {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// `message` comes from the YAML parser, and already mentions the position when it is known.
+    #[error("couldn't parse {path}: {message}")]
+    Parse {
+        path: String,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+
+    #[error("invalid puzzle {path}: {message}")]
+    Validation { path: String, message: String },
+
+    /// A grid given to `Puzzle::check_grid` doesn't match the puzzle's size, or has unknown cells.
+    #[error("invalid grid: {message}")]
+    InvalidGrid { message: String },
+
+    #[error("the puzzle has no solution: {line} cannot be solved")]
+    Contradiction { line: String },
+
+    #[error("the puzzle has no solution")]
+    NoSolution,
+
+    #[error("the puzzle has more than one solution")]
+    MultipleSolutions,
+
+    #[error("gave up after {iterations} iterations{}", if *.cancelled { " (stopped early)" } else { "" })]
+    LimitReached { iterations: u32, cancelled: bool },
+
+    #[error("couldn't solve the puzzle without guessing after {iterations} iterations")]
+    Unsolved { iterations: u32 },
+
+    #[error("{cells} cells differ from the puzzle's known solution")]
+    SolutionMismatch { cells: usize },
+}
+
+pub type Result<T> = std::result::Result<T, PuzzleError>;
+
+impl PuzzleError {
+    pub(crate) fn from_yaml(path: &str, err: serde_yaml::Error) -> PuzzleError {
+        let location = err.location();
+        PuzzleError::Parse {
+            path: path.to_string(),
+            line: location.as_ref().map(|location| location.line()),
+            column: location.as_ref().map(|location| location.column()),
+            message: err.to_string(),
+        }
+    }
+
+    pub(crate) fn validation(path: &str, message: String) -> PuzzleError {
+        PuzzleError::Validation {
+            path: path.to_string(),
+            message,
+        }
+    }
+}
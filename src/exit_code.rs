@@ -0,0 +1,88 @@
+use nonorust::PuzzleError;
+use std::process::ExitCode;
+
+/// The outcomes of every mode of the command line tool, each with its own exit code. Exit code 2 is
+/// left for clap, which uses it when the command line itself is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A command which doesn't solve a puzzle succeeded. Exit code 0.
+    Success,
+    /// The puzzle was solved, and its solution is unique. Exit code 0.
+    Solved,
+    /// The puzzle wasn't solved within the iteration limit or timeout, or without guessing. Exit code 1.
+    NotSolved,
+    /// The puzzle file is invalid, or its clues contradict each other. Exit code 3.
+    InvalidPuzzle,
+    /// The puzzle has more than one solution. Exit code 4.
+    MultipleSolutions,
+    /// The puzzle file, or another input such as a grid to check, couldn't be read or parsed. Exit code 5.
+    ReadError,
+    /// An output file or standard output couldn't be written. Exit code 6.
+    WriteError,
+    /// The solver's result differs from the solution given in the puzzle file. Exit code 7.
+    SolutionMismatch,
+}
+
+impl Outcome {
+    /// The exit code of the outcome.
+    pub fn code(&self) -> u8 {
+        match self {
+            Outcome::Success | Outcome::Solved => 0,
+            Outcome::NotSolved => 1,
+            Outcome::InvalidPuzzle => 3,
+            Outcome::MultipleSolutions => 4,
+            Outcome::ReadError => 5,
+            Outcome::WriteError => 6,
+            Outcome::SolutionMismatch => 7,
+        }
+    }
+
+    /// The name of the outcome in machine readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Solved => "solved",
+            Outcome::NotSolved => "not_solved",
+            Outcome::InvalidPuzzle => "invalid_puzzle",
+            Outcome::MultipleSolutions => "multiple_solutions",
+            Outcome::ReadError => "read_error",
+            Outcome::WriteError => "write_error",
+            Outcome::SolutionMismatch => "solution_mismatch",
+        }
+    }
+
+    /// The outcome to exit with when a command handled several puzzles: the one with the highest exit code.
+    pub fn worst(outcomes: impl IntoIterator<Item = Outcome>) -> Outcome {
+        outcomes
+            .into_iter()
+            .max_by_key(Outcome::code)
+            .unwrap_or(Outcome::Success)
+    }
+
+    pub fn from_result(result: &Result<(), PuzzleError>) -> Outcome {
+        match result {
+            Ok(()) => Outcome::Solved,
+            Err(err) => Outcome::from_error(err),
+        }
+    }
+
+    pub fn from_error(err: &PuzzleError) -> Outcome {
+        match err {
+            PuzzleError::Io { .. } | PuzzleError::Parse { .. } | PuzzleError::InvalidGrid { .. } => {
+                Outcome::ReadError
+            }
+            PuzzleError::Validation { .. } | PuzzleError::Contradiction { .. } | PuzzleError::NoSolution => {
+                Outcome::InvalidPuzzle
+            }
+            PuzzleError::MultipleSolutions => Outcome::MultipleSolutions,
+            PuzzleError::LimitReached { .. } | PuzzleError::Unsolved { .. } => Outcome::NotSolved,
+            PuzzleError::SolutionMismatch { .. } => Outcome::SolutionMismatch,
+        }
+    }
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        ExitCode::from(outcome.code())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::bitset::KnownCells;
+use crate::cell::Cell;
+use crate::line::Line;
+use crate::puzzle::Puzzle;
+
+/// How likely a cell is to be a block, as seen from its row and from its column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockProbability {
+    /// The fraction of the row's remaining arrangements which put a block in the cell.
+    pub row: f64,
+    /// The fraction of the column's remaining arrangements which put a block in the cell.
+    pub col: f64,
+}
+
+impl BlockProbability {
+    /// The mean of the row and column fractions, used to shade the terminal heat map.
+    pub fn combined(&self) -> f64 {
+        (self.row + self.col) / 2.0
+    }
+}
+
+impl Puzzle {
+    /// How likely each cell is to be a block, one row at a time, from the arrangements remaining in its row and in
+    /// its column. Both fractions are 0 or 1 for known cells.
+    pub fn block_probabilities(&self) -> Vec<Vec<BlockProbability>> {
+        let row_fractions: Vec<Vec<f64>> = self.rows().iter().map(block_fractions).collect();
+        let col_fractions: Vec<Vec<f64>> = self.cols().iter().map(block_fractions).collect();
+        self.grid()
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(col, cell)| match cell {
+                        Cell::Block => BlockProbability { row: 1.0, col: 1.0 },
+                        Cell::Space => BlockProbability { row: 0.0, col: 0.0 },
+                        Cell::Unknown => BlockProbability {
+                            row: row_fractions[row][col],
+                            col: col_fractions[col][row],
+                        },
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The block probabilities as CSV, with a header and then one line per cell giving its row and column, counting
+    /// from 1, and the row and column fractions to three decimal places.
+    pub fn heat_map_csv(&self) -> String {
+        let mut csv = String::from("row,col,row_fraction,col_fraction\n");
+        for (row, probabilities) in self.block_probabilities().iter().enumerate() {
+            for (col, probability) in probabilities.iter().enumerate() {
+                csv += &format!("{},{},{:.3},{:.3}\n", row + 1, col + 1, probability.row, probability.col);
+            }
+        }
+        csv
+    }
+}
+
+/// For each cell of a line, the fraction of the line's arrangements which fit its known cells and have a block
+/// there. A line with no arrangements left is contradictory, and gets no blocks.
+fn block_fractions(line: &Line) -> Vec<f64> {
+    let known_cells = KnownCells::from_cells(&line.cells);
+    let mut blocks = vec![0u32; line.cells.len()];
+    let mut arrangements = 0u32;
+    for arrangement in line.potential_solutions.iter().filter(|solution| known_cells.fits(solution)) {
+        arrangements += 1;
+        for (i, count) in blocks.iter_mut().enumerate() {
+            if arrangement.get(i as u8) {
+                *count += 1;
+            }
+        }
+    }
+    blocks
+        .into_iter()
+        .map(|count| if arrangements == 0 { 0.0 } else { count as f64 / arrangements as f64 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::cells_from_text;
+    use crate::line::LineType;
+
+    fn line(clue: Vec<u8>, cells: &str) -> Line {
+        Line::new(LineType::Row, 0, cells_from_text(cells).unwrap(), clue)
+    }
+
+    #[test]
+    fn stalled_line_counts_only_arrangements_which_fit_its_known_cells() {
+        // Line logic can't decide any cell of `??.??` with the clue [1, 1]: of its six arrangements, the four
+        // with the space in the middle remain, and each unknown cell is a block in half of them.
+        assert_eq!(block_fractions(&line(vec![1, 1], "??.??")), [0.5, 0.5, 0.0, 0.5, 0.5]);
+        assert_eq!(block_fractions(&line(vec![2], "????")), [1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 1.0 / 3.0]);
+    }
+
+    #[test]
+    fn contradictory_line_has_no_blocks() {
+        assert_eq!(block_fractions(&line(vec![2], "#.#")), [0.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::cell::Cell;
+use crate::line::{Line, LineType};
+use crate::observer::NullObserver;
+use crate::puzzle::{Puzzle, SolveOptions, SolveStats};
+use serde::Serialize;
+
+/// Cells which can be decided from the current state of a puzzle, and how.
+#[derive(Debug, Clone, Serialize)]
+pub struct Hint {
+    /// The label of the line whose clue decides the cells, or `None` if they can only be found by probing.
+    pub line: Option<String>,
+    /// The row, column and value of each cell which can be decided.
+    pub cells: Vec<(u8, u8, Cell)>,
+}
+
+impl Puzzle {
+    /// Finds the first line which line logic can make progress on, on its own, from the puzzle's current
+    /// cells. If there is none, falls back to probing for a single cell. Lines whose known cells already
+    /// contradict their clue are skipped.
+    pub fn hint(&self) -> Option<Hint> {
+        for line in self.rows.iter().chain(self.cols.iter()) {
+            if line.has_contradiction() {
+                continue;
+            }
+            let mut solved_line = line.clone();
+            while solved_line.solve().1 {}
+            let cells: Vec<(u8, u8, Cell)> = (0..line.length())
+                .filter(|&i| line.get_cell(i) != solved_line.get_cell(i))
+                .map(|i| {
+                    let (row, col) = cell_position(line, i);
+                    (row, col, solved_line.get_cell(i))
+                })
+                .collect();
+            if !cells.is_empty() {
+                return Some(Hint {
+                    line: Some(line.label()),
+                    cells,
+                });
+            }
+        }
+
+        let mut probed = self.clone();
+        let mut stats = SolveStats::default();
+        if !probed.probe(&SolveOptions::default(), None, &mut stats, &mut NullObserver) {
+            return None;
+        }
+        for row in 0..self.row_count() {
+            for col in 0..self.col_count() {
+                if self.cell(row, col) != probed.cell(row, col) {
+                    return Some(Hint {
+                        line: None,
+                        cells: vec![(row, col, probed.cell(row, col))],
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+fn cell_position(line: &Line, cell_index: u8) -> (u8, u8) {
+    match line.axis {
+        LineType::Row => (line.index, cell_index),
+        LineType::Col => (cell_index, line.index),
+    }
+}
@@ -0,0 +1,143 @@
+use crate::cell::{Cell, cells_to_text};
+use crate::error::{IN_MEMORY_SOURCE, PuzzleError, Result};
+use crate::puzzle::Puzzle;
+use crate::svg::{THICK_LINE_SPACING, escape_xml, printed_clue};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; user-select: none; }
+td { width: 1.6em; height: 1.6em; padding: 0; text-align: center; font-size: 0.8em; }
+td.col-clue { vertical-align: bottom; background: #eee; line-height: 1.6em; }
+td.row-clue { text-align: right; padding-right: 0.4em; background: #eee; white-space: nowrap; }
+td.cell { border: 1px solid #999; cursor: pointer; }
+td.cell.block { background: #000; }
+td.cell.space::after { content: '\\2219'; color: #999; }
+td.thick-left { border-left: 2px solid #000; }
+td.thick-top { border-top: 2px solid #000; }
+td.thick-right { border-right: 2px solid #000; }
+td.thick-bottom { border-bottom: 2px solid #000; }
+#message { min-height: 1.5em; margin: 1em 0; font-weight: bold; }
+footer { margin-top: 2em; color: #555; font-size: 0.8em; }";
+
+// Clicking a cell cycles it from unknown to block to space; right clicking marks a space.
+const SCRIPT: &str = "const cells = document.querySelectorAll('td.cell');
+const states = ['', 'block', 'space'];
+function setState(cell, state) {
+  cell.classList.remove('block', 'space');
+  if (state) cell.classList.add(state);
+}
+function stateOf(cell) {
+  return cell.classList.contains('block') ? 'block' : cell.classList.contains('space') ? 'space' : '';
+}
+cells.forEach(cell => {
+  cell.addEventListener('click', () => setState(cell, states[(states.indexOf(stateOf(cell)) + 1) % 3]));
+  cell.addEventListener('contextmenu', event => {
+    event.preventDefault();
+    setState(cell, stateOf(cell) === 'space' ? '' : 'space');
+  });
+});
+document.getElementById('check').addEventListener('click', () => {
+  let wrong = 0, unknown = 0;
+  cells.forEach(cell => {
+    const expected = solution[cell.dataset.row][cell.dataset.col] === '#' ? 'block' : 'space';
+    const state = stateOf(cell);
+    if (state === '') { if (expected === 'block') unknown++; }
+    else if (state !== expected) wrong++;
+  });
+  const message = document.getElementById('message');
+  if (wrong > 0) message.textContent = wrong + (wrong === 1 ? ' cell is' : ' cells are') + ' wrong';
+  else if (unknown > 0) message.textContent = 'No mistakes so far - ' + unknown + ' blocks to go';
+  else message.textContent = 'Puzzle solved - well done!';
+});
+document.getElementById('clear').addEventListener('click', () => {
+  cells.forEach(cell => setState(cell, ''));
+  document.getElementById('message').textContent = '';
+});";
+
+impl Puzzle {
+    /// Writes the puzzle as a standalone HTML page, with its clues and an empty grid which can be played in a
+    /// browser by clicking cells. The page checks the player's cells against the puzzle's known solution, or
+    /// else against the puzzle's cells, which must then all be solved.
+    pub fn to_html(&self) -> Result<String> {
+        let solution = match &self.solution {
+            Some(solution) => solution.clone(),
+            None if self.grid().iter().flatten().all(|cell| *cell != Cell::Unknown) => self.grid(),
+            None => {
+                return Err(PuzzleError::validation(
+                    IN_MEMORY_SOURCE,
+                    String::from("a puzzle can only be exported as HTML once its solution is known"),
+                ));
+            }
+        };
+        let solution_rows: Vec<String> = solution.iter().map(|row| cells_to_text(row)).collect();
+        let title = escape_xml(&self.title);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        if !self.author.is_empty() {
+            html += &format!("<p>by {}</p>\n", escape_xml(&self.author));
+        }
+        html += "<table>\n<tr><td></td>";
+        let col_count = self.col_count() as usize;
+        let row_count = self.row_count() as usize;
+        for (col, clue) in self.col_clues.iter().enumerate() {
+            let numbers: Vec<String> = printed_clue(clue).iter().map(|n| n.to_string()).collect();
+            html += &format!(
+                "<td class=\"col-clue{}\">{}</td>",
+                thick_classes(None, Some(col), row_count, col_count),
+                numbers.join("<br>")
+            );
+        }
+        html += "</tr>\n";
+        for (row, clue) in self.row_clues.iter().enumerate() {
+            let numbers: Vec<String> = printed_clue(clue).iter().map(|n| n.to_string()).collect();
+            html += &format!(
+                "<tr><td class=\"row-clue{}\">{}</td>",
+                thick_classes(Some(row), None, row_count, col_count),
+                numbers.join(" ")
+            );
+            for col in 0..col_count {
+                html += &format!(
+                    "<td class=\"cell{}\" data-row=\"{row}\" data-col=\"{col}\"></td>",
+                    thick_classes(Some(row), Some(col), row_count, col_count)
+                );
+            }
+            html += "</tr>\n";
+        }
+        html += "</table>\n<p><button id=\"check\">Check</button> <button id=\"clear\">Clear</button></p>\n";
+        html += "<div id=\"message\"></div>\n<p>Click a cell to mark it as a block, then as a space; right click to mark a space.</p>\n";
+        let footer: Vec<String> = [&self.license, &self.source]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .map(|text| escape_xml(text))
+            .collect();
+        if !footer.is_empty() {
+            html += &format!("<footer>{}</footer>\n", footer.join("<br>"));
+        }
+        let solution_json = serde_json::to_string(&solution_rows).unwrap_or_else(|_| String::from("[]"));
+        html += &format!("<script>\nconst solution = {solution_json};\n{SCRIPT}\n</script>\n</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+/// The classes which draw the thick lines every few cells and around the grid, for a cell in the given row and
+/// column, or a clue for the given row or column.
+fn thick_classes(row: Option<usize>, col: Option<usize>, row_count: usize, col_count: usize) -> String {
+    let mut classes = String::new();
+    if let Some(col) = col {
+        if col.is_multiple_of(THICK_LINE_SPACING) {
+            classes += " thick-left";
+        }
+        if col + 1 == col_count {
+            classes += " thick-right";
+        }
+    }
+    if let Some(row) = row {
+        if row.is_multiple_of(THICK_LINE_SPACING) {
+            classes += " thick-top";
+        }
+        if row + 1 == row_count {
+            classes += " thick-bottom";
+        }
+    }
+    classes
+}
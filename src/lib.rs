@@ -0,0 +1,56 @@
+//! A nonogram solver.
+//!
+//! Load a puzzle with [`PuzzleConfig::build`], solve it with [`Puzzle::solve`] (or
+//! [`Puzzle::solve_with_observer`] to follow its progress), then read the grid with [`Puzzle::cell`]
+//! or [`Puzzle::grid`] and the outcome from the returned [`SolveStats`].
+//!
+//! ```no_run
+//! use nonorust::{PuzzleConfig, SolveOptions};
+//!
+//! let mut puzzle = PuzzleConfig::build(String::from("data/spades.nonogram.yaml")).unwrap();
+//! let stats = puzzle.solve(&SolveOptions::default());
+//! println!("Solved: {} in {} iterations", stats.solved, stats.iterations);
+//! ```
+
+mod bitset;
+mod cancellation;
+mod cell;
+mod check;
+mod decision_order;
+mod error;
+mod heat_map;
+mod hint;
+mod html;
+mod line;
+mod line_algorithms;
+mod non_format;
+mod observer;
+mod png_image;
+mod puzzle;
+mod puzzle_builder;
+mod puzzle_factory;
+mod render;
+mod search;
+mod solution;
+mod solver_state;
+mod svg;
+mod transform;
+
+pub use cancellation::CancellationToken;
+pub use cell::{Cell, cells_from_text, cells_to_text};
+pub use check::LineMismatch;
+pub use decision_order::{CellDecision, DecisionRecorder};
+pub use error::{PuzzleError, Result};
+pub use heat_map::BlockProbability;
+pub use hint::Hint;
+pub use line::{Line, LineType};
+pub use line_algorithms::{clue_from_cells, clues_from_grid};
+pub use observer::{LoggingObserver, NullObserver, SolveObserver};
+pub use png_image::{Colour, PngOptions};
+pub use puzzle::{Puzzle, SolveOptions, SolveStats, clue_as_string};
+pub use puzzle_builder::PuzzleBuilder;
+pub use puzzle_factory::{PuzzleConfig, PuzzleFormat};
+pub use render::{ColorChoice, RenderOptions, Theme};
+pub use solution::CellMismatch;
+pub use solver_state::SolverState;
+pub use svg::SvgOptions;
@@ -1,9 +1,11 @@
-use crate::cell::{cell_vector_contains_unknown, Cell};
-use crate::line_algorithms::{generate_all_potential_solutions_for_clue, factorial};
+use crate::bitset::{Bitset, KnownCells};
+use crate::cell::{cell_vector_contains_unknown, display_cell_vector, Cell};
+use crate::line_algorithms::{binomial, clue_from_cells, generate_all_potential_solutions_for_clue};
 use colored::Colorize;
+use serde::Serialize;
 
-#[derive(Debug, Clone)]
-
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LineType {
     Row,
     Col,
@@ -15,13 +17,13 @@ pub struct Line {
     pub index: u8,
     pub clue: Vec<u8>,
     pub cells: Vec<Cell>,
-    pub potential_solutions: Vec<Vec<Cell>>,
+    pub(crate) potential_solutions: Vec<Bitset>,
 }
 
 impl Line {
-    pub fn new(axis: LineType, index: u8, cells: Vec<Cell>, clue: Vec<u8>) -> Line {
+    pub(crate) fn new(axis: LineType, index: u8, cells: Vec<Cell>, clue: Vec<u8>) -> Line {
         let potential_solutions =
-            generate_all_potential_solutions_for_clue(clue.clone(), cells.len() as i8);
+            generate_all_potential_solutions_for_clue(clue.clone(), cells.len() as i16);
         Line {
             axis,
             index,
@@ -39,7 +41,7 @@ impl Line {
         self.cells[index as usize].clone()
     }
 
-    pub fn set_cell(&mut self, index: u8, cell: Cell) {
+    pub(crate) fn set_cell(&mut self, index: u8, cell: Cell) {
         self.cells[index as usize] = cell;
     }
 
@@ -50,7 +52,7 @@ impl Line {
         }
     }
 
-    pub fn solve(&mut self) -> (bool, bool) {
+    pub(crate) fn solve(&mut self) -> (bool, bool) {
         let line_solved: bool;
         let mut progress_made: bool;
         if self.is_solved() {
@@ -67,12 +69,7 @@ impl Line {
         }
 
         if self.potential_solutions.len() == 1 {
-            let potential_solutions = self.potential_solutions.clone();
-            for solution in potential_solutions {
-                for (i, cell) in solution.iter().enumerate() {
-                    self.set_cell(i as u8, cell.clone());
-                }
-            }
+            self.cells = self.potential_solutions[0].to_cells(self.length());
             self.potential_solutions.clear();
             // line_solved = false is intentional here, even though the line is solved at this point,
             // because we want it to be reported back to the puzzle solver as unsolved but with progress made,
@@ -97,11 +94,26 @@ impl Line {
         self.potential_solutions.is_empty()
     }
 
-    pub fn dump(&self) {
-        let mut cells_display = String::new();
-        for cell in &self.cells {
-            cells_display += &cell.display();
+    pub fn potential_solution_count(&self) -> usize {
+        self.potential_solutions.len()
+    }
+
+    /// A line is contradictory when none of its remaining potential solutions fit its known cells or,
+    /// once it has been solved, when its cells are not all known or do not match its clue.
+    pub fn has_contradiction(&self) -> bool {
+        if self.is_solved() {
+            cell_vector_contains_unknown(&self.cells) || clue_from_cells(&self.cells) != self.clue
+        } else {
+            let known_cells = KnownCells::from_cells(&self.cells);
+            !self
+                .potential_solutions
+                .iter()
+                .any(|solution| known_cells.fits(solution))
         }
+    }
+
+    pub fn dump(&self) {
+        let cells_display = display_cell_vector(&self.cells);
         println!(
             "{}:  Clue: {:?}  Length: {}  Potential Solutions Remaining: {}  Cells: {}",
             self.label(),
@@ -112,17 +124,19 @@ impl Line {
         );
     }
 
+    /// The number of arrangements of the clue in an empty line: choosing where the clue's runs go among the free
+    /// cells, C(length - blocks + 1, runs). Saturates at `u128::MAX` rather than overflowing.
     pub fn calculate_all_potential_solutions(&self) -> u128 {
-        let sum_of_blocks:u128 = self.clue.iter().map(|&x| x as u128).sum();
-        let n:u128 = (self.length() as u128 - sum_of_blocks) + 1;
-        factorial(n) / (factorial(self.clue.len() as u128) * factorial(n - self.clue.len() as u128))
+        let sum_of_blocks: u128 = self.clue.iter().map(|&x| x as u128).sum();
+        let n: u128 = (self.length() as u128 - sum_of_blocks) + 1;
+        binomial(n, self.clue.len() as u128)
     }
 
     pub fn dump_potential_solutions(&self) {
         println!("Potential solutions for {}:", self.label());
         for solution in &self.potential_solutions {
             let mut solution_str = String::new();
-            for cell in solution {
+            for cell in solution.to_cells(self.length()) {
                 solution_str += &cell.display()
             }
             println!("{}", solution_str.blue());
@@ -1,44 +1,47 @@
-use crate::cell::{Cell, generate_cell_vector};
+use crate::bitset::{Bitset, KnownCells};
+use crate::cell::Cell;
 use crate::line::Line;
 
-pub fn factorial(number: u128) -> u128 {
-    let mut factorial: u128 = 1;
-    for i in 1..(number + 1) {
-        println!("i = {i}, number = {number}");
-        factorial *= i;
+/// The binomial coefficient C(n, k), computed one factor at a time so that it stays exact for as long as it fits,
+/// and saturating at `u128::MAX` once it doesn't.
+pub(crate) fn binomial(n: u128, k: u128) -> u128 {
+    if k > n {
+        return 0;
     }
-    factorial
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 1..=k {
+        // result * (n - k + i) is always divisible by i, as it is i times C(n - k + i, i).
+        match result.checked_mul(n - k + i) {
+            Some(product) => result = product / i,
+            None => return u128::MAX,
+        }
+    }
+    result
 }
 
-pub fn generate_all_potential_solutions_for_clue(clue: Vec<u8>, length: i8) -> Vec<Vec<Cell>> {
+pub(crate) fn generate_all_potential_solutions_for_clue(clue: Vec<u8>, length: i16) -> Vec<Bitset> {
     if clue.is_empty() {
-        let mut solutions: Vec<Vec<Cell>> = Vec::new();
-        let cell_vector = generate_cell_vector(Cell::Space, length as u8);
-        solutions.push(cell_vector);
-        solutions
+        vec![Bitset::new()]
     } else {
-        let starts = length - clue[0] as i8;
+        let starts = length - clue[0] as i16;
         if clue.len() == 1 {
-            let mut solutions: Vec<Vec<Cell>> = Vec::new();
+            let mut solutions: Vec<Bitset> = Vec::new();
             for i in 0..starts + 1 {
-                let mut solution: Vec<Cell> = Vec::new();
-                solution.append(&mut generate_cell_vector(Cell::Space, i as u8));
-                solution.append(&mut generate_cell_vector(Cell::Block, clue[0]));
-                solution.append(&mut generate_cell_vector(Cell::Space, (starts - i) as u8));
+                let mut solution = Bitset::new();
+                solution.set_range(i as u8, clue[0]);
                 solutions.push(solution);
             }
             solutions
         } else {
-            let mut solutions: Vec<Vec<Cell>> = Vec::new();
+            let mut solutions: Vec<Bitset> = Vec::new();
             for i in 0..starts {
+                let offset = i as u8 + clue[0] + 1;
                 for j in
                     generate_all_potential_solutions_for_clue(clue[1..].to_owned(), starts - i - 1)
                 {
-                    let mut solution: Vec<Cell> = Vec::new();
-                    solution.append(&mut generate_cell_vector(Cell::Space, i as u8));
-                    solution.append(&mut generate_cell_vector(Cell::Block, clue[0]));
-                    solution.push(Cell::Space);
-                    solution.append(&mut j.clone());
+                    let mut solution = j.shifted(offset);
+                    solution.set_range(i as u8, clue[0]);
                     solutions.push(solution);
                 }
             }
@@ -47,71 +50,154 @@ pub fn generate_all_potential_solutions_for_clue(clue: Vec<u8>, length: i8) -> V
     }
 }
 
-impl Line {
-    pub fn find_cells_which_are_same_in_all_potential_solutions(&mut self) -> bool {
-        let mut progress_made = false;
-        let cells = &self.cells.clone();
-        for (cell_index, cell) in cells.iter().enumerate() {
-            if cell == &Cell::Unknown {
-                let mut found_non_block_cell = false;
-                for solution in self.potential_solutions.iter() {
-                    if solution[cell_index] != Cell::Block {
-                        found_non_block_cell = true;
-                        break;
-                    }
-                }
-                if !found_non_block_cell {
-                    self.set_cell(cell_index as u8, Cell::Block);
-                    progress_made = true;
-                } else {
-                    let mut found_non_space_cell = false;
-                    for solution in self.potential_solutions.iter() {
-                        if solution[cell_index] != Cell::Space {
-                            found_non_space_cell = true;
-                            break;
-                        }
-                    }
-                    if !found_non_space_cell {
-                        self.set_cell(cell_index as u8, Cell::Space);
-                        progress_made = true;
-                    }
+/// Which numbers of a clue are already placed in a partly solved line, as a solver would see them: runs of blocks
+/// are matched to the clue in order from each end of the line, as far as the first unknown cell. In a complete line
+/// which fits its clue, every number is placed.
+pub(crate) fn placed_clue_numbers(clue: &[u8], cells: &[Cell]) -> Vec<bool> {
+    let mut placed = vec![false; clue.len()];
+    if !cells.contains(&Cell::Unknown) && clue_from_cells(cells) == clue {
+        placed.fill(true);
+        return placed;
+    }
+    let from_start = placed_from_end(clue.iter(), cells.iter());
+    let from_end = placed_from_end(clue.iter().rev(), cells.iter().rev()).min(clue.len() - from_start);
+    placed[..from_start].fill(true);
+    placed[clue.len() - from_end..].fill(true);
+    placed
+}
+
+/// How many clue numbers, taken in order, match the runs of blocks from one end of a line before the first unknown
+/// cell. A run only counts once the cell after it is known to be a space, or it reaches the other end.
+fn placed_from_end<'a>(mut clue: impl Iterator<Item = &'a u8>, cells: impl Iterator<Item = &'a Cell>) -> usize {
+    let mut placed = 0;
+    let mut run_length: u8 = 0;
+    for cell in cells {
+        match cell {
+            Cell::Unknown => return placed,
+            Cell::Block => run_length += 1,
+            Cell::Space if run_length > 0 => {
+                if clue.next() != Some(&run_length) {
+                    return placed;
                 }
+                placed += 1;
+                run_length = 0;
             }
+            Cell::Space => {}
         }
-        progress_made
     }
+    if run_length > 0 && clue.next() == Some(&run_length) {
+        placed += 1;
+    }
+    placed
+}
+
+/// The clue produced by the runs of blocks in a line of cells. Unknown cells are treated as spaces.
+pub fn clue_from_cells(cells: &[Cell]) -> Vec<u8> {
+    let mut clue = Vec::new();
+    let mut run_length: u8 = 0;
+    for cell in cells {
+        if *cell == Cell::Block {
+            run_length += 1;
+        } else if run_length > 0 {
+            clue.push(run_length);
+            run_length = 0;
+        }
+    }
+    if run_length > 0 {
+        clue.push(run_length);
+    }
+    clue
+}
+
+impl Line {
+    pub(crate) fn find_cells_which_are_same_in_all_potential_solutions(&mut self) -> bool {
+        let Some(first_solution) = self.potential_solutions.first() else {
+            return false;
+        };
+        // A cell is a block in every solution if it survives AND-ing them all together,
+        // and a space in every solution if it is still clear after OR-ing them all together.
+        let mut always_block = *first_solution;
+        let mut ever_block = *first_solution;
+        for solution in self.potential_solutions.iter().skip(1) {
+            always_block = always_block.and(solution);
+            ever_block = ever_block.or(solution);
+        }
+        let always_space = Bitset::filled(self.length()).and_not(&ever_block);
 
-    // pub fn remove_solutions_which_do_not_fit_known_cells(&mut self) -> bool {
-    //     let mut progress_made = false;
-    //     self.potential_solutions.retain(|solution| {
-    //         let mut retain = true;
-    //         for (cell_index, cell) in self.cells.iter().enumerate() {
-    //             if cell != &Cell::Unknown && solution[cell_index] != *cell {
-    //                 progress_made = true;
-    //                 retain = false;
-    //                 break;
-    //             }
-    //         }
-    //         retain
-    //     });
-    //     progress_made
-    // }
-
-    pub fn remove_solutions_which_do_not_fit_known_cells(&mut self) -> bool {
         let mut progress_made = false;
-        let cells = &self.cells.clone();
-        for (cell_index, cell) in cells.iter().enumerate() {
-            if cell != &Cell::Unknown {
-                self.potential_solutions.retain(|solution| {
-                    if solution[cell_index] == *cell {
-                        true
-                    } else {
-                        progress_made = true;
-                        false
-                    }
-                });
+        for cell_index in 0..self.length() {
+            if self.get_cell(cell_index) == Cell::Unknown {
+                if always_block.get(cell_index) {
+                    self.set_cell(cell_index, Cell::Block);
+                    progress_made = true;
+                } else if always_space.get(cell_index) {
+                    self.set_cell(cell_index, Cell::Space);
+                    progress_made = true;
+                }
             }
         }
         progress_made
     }
+
+    pub(crate) fn remove_solutions_which_do_not_fit_known_cells(&mut self) -> bool {
+        let known_cells = KnownCells::from_cells(&self.cells);
+        let solutions_before = self.potential_solutions.len();
+        self.potential_solutions
+            .retain(|solution| known_cells.fits(solution));
+        self.potential_solutions.len() != solutions_before
+    }
+}
+
+/// The row and column clues of a complete grid, given one row at a time. Unknown cells are treated as spaces.
+pub fn clues_from_grid(grid: &[Vec<Cell>]) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let row_clues = grid.iter().map(|row| clue_from_cells(row)).collect();
+    let col_count = grid.first().map_or(0, |row| row.len());
+    let col_clues = (0..col_count)
+        .map(|col| {
+            let column: Vec<Cell> = grid.iter().map(|row| row[col].clone()).collect();
+            clue_from_cells(&column)
+        })
+        .collect();
+    (row_clues, col_clues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::cells_from_text;
+
+    fn placed(clue: &[u8], cells: &str) -> Vec<bool> {
+        placed_clue_numbers(clue, &cells_from_text(cells).unwrap())
+    }
+
+    #[test]
+    fn run_reaching_the_edge_of_the_line_is_placed_once_a_space_follows_it() {
+        assert_eq!(placed(&[1, 2], "?.##"), [false, true]);
+        assert_eq!(placed(&[2, 1], "##.?"), [true, false]);
+    }
+
+    #[test]
+    fn run_ending_at_an_unknown_cell_is_not_placed() {
+        // The unknown cell could still make the run longer.
+        assert_eq!(placed(&[2, 1], "##?.."), [false, false]);
+        assert_eq!(placed(&[1, 2], "..?##"), [false, false]);
+    }
+
+    #[test]
+    fn complete_line_which_does_not_fit_its_clue_only_places_matching_runs() {
+        assert_eq!(placed(&[2, 1], "##.##"), [true, false]);
+        assert_eq!(placed(&[1, 1], "##.##"), [false, false]);
+    }
+
+    #[test]
+    fn complete_line_which_fits_its_clue_places_every_number() {
+        assert_eq!(placed(&[2, 1], "##..#"), [true, true]);
+        assert_eq!(placed(&[], "....."), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn number_claimed_from_both_ends_is_placed_once() {
+        assert_eq!(placed(&[2], "##.?.##"), [true]);
+        assert_eq!(placed(&[2, 2], "##.?.##"), [true, true]);
+    }
 }
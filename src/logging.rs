@@ -1,12 +1,24 @@
 use flexi_logger::{style, DeferredNow};
 use log::Record;
+use nonorust::ColorChoice;
 
-pub fn configure_flexi_logger(debug: bool) -> Result<(), Box<dyn std::error::Error>> {
+/// Logs to stdout, or to stderr when stdout is kept for machine readable output, colouring each line by its level
+/// when `color_choice` allows colour on the stream being logged to.
+pub fn configure_flexi_logger(
+    debug: bool,
+    log_to_stderr: bool,
+    color_choice: ColorChoice,
+) -> Result<(), Box<dyn std::error::Error>> {
     let logging_level = if debug { "debug" } else { "info" };
-    flexi_logger::Logger::try_with_str(logging_level)?
-        .log_to_stdout()
+    let logger = flexi_logger::Logger::try_with_str(logging_level)?;
+    let (logger, color) = if log_to_stderr {
+        (logger.log_to_stderr(), color_choice.use_color_for(&std::io::stderr()))
+    } else {
+        (logger.log_to_stdout(), color_choice.use_color())
+    };
+    logger
         .set_palette("1;5;32;3;-".parse()?)
-        .format(custom_logging_format)
+        .format(if color { custom_logging_format } else { plain_logging_format })
         .start()?;
     Ok(())
 }
@@ -25,4 +37,19 @@ pub fn custom_logging_format(
         style(level).paint(record.line().unwrap_or(0).to_string()),
     )?;
     write!(w, "{}", record.args())
-}
\ No newline at end of file
+}
+
+pub fn plain_logging_format(
+    w: &mut dyn std::io::Write,
+    _now: &mut DeferredNow,
+    record: &Record,
+) -> Result<(), std::io::Error> {
+    write!(
+        w,
+        "{}: {}:{} {}",
+        record.level(),
+        record.file().unwrap_or("<unnamed>"),
+        record.line().unwrap_or(0),
+        record.args()
+    )
+}
@@ -1,43 +1,33 @@
-pub mod puzzle;
-pub mod cell;
-pub mod puzzle_factory;
-pub mod line;
-pub mod line_algorithms;
-pub mod cli;
-pub mod logging;
-use crate::puzzle_factory::PuzzleConfig;
+mod cli;
+mod commands;
+mod exit_code;
+mod logging;
 use clap::Parser;
-use crate::cli::Args;
+use std::process::ExitCode;
+use crate::cli::{Cli, Command, OutputFormat};
 use crate::logging::configure_flexi_logger;
 
-// use flexi_logger::Logger;
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
-    configure_flexi_logger(args.debug).expect("Failed to configure logger - quitting");
-    
-    let puzzle_file_path = args.path;
-    let max_iterations = args.max_iterations;
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let format = cli.global.format;
+    let render_options = cli.global.render_options();
+    colored::control::set_override(render_options.color);
+    configure_flexi_logger(cli.global.debug, format == OutputFormat::Json, cli.global.color.into())
+        .expect("Failed to configure logger - quitting");
 
-    let puzzle_factory_result = PuzzleConfig::build(puzzle_file_path.parse().unwrap());
-    let mut puzzle = match puzzle_factory_result {
-        Ok(puzzle) => puzzle,
-        Err(e) => {
-            eprintln!("Error loading puzzle: {e}");
-            return Ok(());
-        }
+    let outcome = match cli.into_command() {
+        Command::Solve(args) => commands::solve::run(&args, format, &render_options),
+        Command::Validate(args) => commands::validate::run(&args, format),
+        Command::Convert(args) => commands::convert::run(&args, format),
+        Command::Generate(args) => commands::generate::run(&args, format),
+        Command::Hint(args) => commands::hint::run(&args, format),
+        Command::Play(args) => commands::play::run(&args, &render_options),
+        Command::Batch(args) => commands::batch::run(&args, format, &render_options),
+        Command::Info(args) => commands::info::run(&args, format),
+        Command::Check(args) => commands::check::run(&args, format),
+        Command::Transform(args) => commands::transform::run(&args, format),
+        Command::Export(args) => commands::export::run(&args),
+        Command::HeatMap(args) => commands::heat_map::run(&args, &render_options),
     };
-
-    log::info!(
-        "Starting Nonogram solver with file: {puzzle_file_path} and max iterations set to: {max_iterations}");
-
-    let (iterations_needed_to_solve,puzzle_solved) = puzzle.solve(max_iterations);
-    puzzle.dump();
-    if puzzle_solved {
-        println!("Puzzle solved!");
-    } else {
-        println!("Puzzle NOT solved!");
-    }
-    println!("Iterations used: {iterations_needed_to_solve}");
-
-    Ok(())
+    outcome.into()
 }
@@ -0,0 +1,220 @@
+use crate::cell::Cell;
+use crate::error::{PuzzleError, Result};
+use crate::puzzle_factory::PuzzleConfig;
+
+// The nonogram-db `.non` format is line based: a keyword, optionally followed by a value, on each line,
+// with the clues listed one per line, as comma separated numbers, after a `rows` or `columns` line.
+// Keywords which this project has no use for, such as `copyright` or `color`, are skipped.
+
+/// Parses a puzzle in the nonogram-db `.non` format. `source_name` is only used in error messages.
+pub(crate) fn parse_non(text: &str, source_name: &str) -> Result<PuzzleConfig> {
+    let mut puzzle_config = PuzzleConfig {
+        title: String::new(),
+        by: None,
+        license: None,
+        source: None,
+        rows: Vec::new(),
+        columns: Vec::new(),
+        givens: None,
+        solution: None,
+    };
+    let mut width: Option<usize> = None;
+    let mut height: Option<usize> = None;
+    let mut goal: Option<(String, usize)> = None;
+    let mut lines = text.lines().enumerate().peekable();
+    while let Some((line_index, line)) = lines.next() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, value) = match line.split_once(char::is_whitespace) {
+            Some((keyword, value)) => (keyword, unquote(value.trim())),
+            None => (line, String::new()),
+        };
+        match keyword {
+            "title" => puzzle_config.title = value,
+            "by" => puzzle_config.by = Some(value),
+            "license" => puzzle_config.license = Some(value),
+            "catalogue" => puzzle_config.source = Some(value),
+            "width" => width = Some(parse_number(&value, source_name, line_number)?),
+            "height" => height = Some(parse_number(&value, source_name, line_number)?),
+            "goal" => goal = Some((value, line_number)),
+            "rows" | "columns" => {
+                let mut clues = Vec::new();
+                while let Some((clue_index, clue_line)) = lines.peek() {
+                    let clue_line = clue_line.trim();
+                    if clue_line.is_empty() || !clue_line.starts_with(|c: char| c.is_ascii_digit()) {
+                        break;
+                    }
+                    clues.push(parse_clue(clue_line, source_name, clue_index + 1)?);
+                    lines.next();
+                }
+                if keyword == "rows" {
+                    puzzle_config.rows = clues;
+                } else {
+                    puzzle_config.columns = clues;
+                }
+            }
+            _ => log::debug!("Skipping '{keyword}' at line {line_number} of {source_name}"),
+        }
+    }
+    if let Some(height) = height
+        && height != puzzle_config.rows.len()
+    {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("the height is {height}, but there are {} row clues", puzzle_config.rows.len()),
+        ));
+    }
+    if let Some(width) = width
+        && width != puzzle_config.columns.len()
+    {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("the width is {width}, but there are {} column clues", puzzle_config.columns.len()),
+        ));
+    }
+    if let Some((goal, line_number)) = goal {
+        puzzle_config.solution = Some(parse_goal(&goal, puzzle_config.columns.len(), source_name, line_number)?);
+    }
+    Ok(puzzle_config)
+}
+
+/// Writes a puzzle in the nonogram-db `.non` format.
+pub(crate) fn write_non(puzzle_config: &PuzzleConfig) -> String {
+    let mut non = String::new();
+    if let Some(source) = &puzzle_config.source {
+        non += &format!("catalogue {}\n", quote(source));
+    }
+    non += &format!("title {}\n", quote(&puzzle_config.title));
+    if let Some(by) = &puzzle_config.by {
+        non += &format!("by {}\n", quote(by));
+    }
+    if let Some(license) = &puzzle_config.license {
+        non += &format!("license {license}\n");
+    }
+    non += &format!("width {}\n", puzzle_config.columns.len());
+    non += &format!("height {}\n", puzzle_config.rows.len());
+    for (keyword, clues) in [("rows", &puzzle_config.rows), ("columns", &puzzle_config.columns)] {
+        non += &format!("\n{keyword}\n");
+        for clue in clues {
+            if clue.is_empty() {
+                non += "0\n";
+            } else {
+                let numbers: Vec<String> = clue.iter().map(|n| n.to_string()).collect();
+                non += &format!("{}\n", numbers.join(","));
+            }
+        }
+    }
+    if let Some(solution) = &puzzle_config.solution {
+        let goal: String = solution
+            .concat()
+            .chars()
+            .map(|c| if Cell::from_char(c) == Some(Cell::Block) { '1' } else { '0' })
+            .collect();
+        non += &format!("\ngoal \"{goal}\"\n");
+    }
+    non
+}
+
+/// Quotes a value, escaping any quotes and backslashes in it with a backslash.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Removes the quotes around a value, if it has them, along with the backslashes escaping characters in it.
+fn unquote(value: &str) -> String {
+    let Some(quoted) = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut unquoted = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+/// Splits a `goal`, which lists every cell from the top left as `1` for a block or `0` for a space, into rows.
+fn parse_goal(goal: &str, width: usize, source_name: &str, line_number: usize) -> Result<Vec<String>> {
+    let cells: Option<String> = goal
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '1' => Some(Cell::Block.to_char()),
+            '0' => Some(Cell::Space.to_char()),
+            _ => None,
+        })
+        .collect();
+    match cells {
+        Some(cells) if width > 0 && cells.len() % width == 0 => Ok(cells
+            .as_bytes()
+            .chunks(width)
+            .map(|row| String::from_utf8_lossy(row).into_owned())
+            .collect()),
+        _ => Err(PuzzleError::Parse {
+            path: source_name.to_string(),
+            line: Some(line_number),
+            column: None,
+            message: format!("invalid goal at line {line_number}"),
+        }),
+    }
+}
+
+fn parse_number(value: &str, source_name: &str, line_number: usize) -> Result<usize> {
+    value.parse().map_err(|_| PuzzleError::Parse {
+        path: source_name.to_string(),
+        line: Some(line_number),
+        column: None,
+        message: format!("expected a number but found '{value}' at line {line_number}"),
+    })
+}
+
+fn parse_clue(clue_line: &str, source_name: &str, line_number: usize) -> Result<Vec<u8>> {
+    let mut clue = Vec::new();
+    for number in clue_line.split(',') {
+        match number.trim().parse::<u8>() {
+            Ok(0) => (),
+            Ok(number) => clue.push(number),
+            Err(_) => {
+                return Err(PuzzleError::Parse {
+                    path: source_name.to_string(),
+                    line: Some(line_number),
+                    column: None,
+                    message: format!("invalid clue '{clue_line}' at line {line_number}"),
+                });
+            }
+        }
+    }
+    Ok(clue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_and_backslashes_survive_a_round_trip() {
+        let puzzle_config = PuzzleConfig {
+            title: String::from(r#"The "quoted" title"#),
+            by: Some(String::from(r"C:\puzzles\")),
+            license: None,
+            source: Some(String::from(r#"\"#)),
+            rows: vec![vec![1], vec![]],
+            columns: vec![vec![1], vec![]],
+            givens: None,
+            solution: None,
+        };
+        let non = write_non(&puzzle_config);
+        let parsed = parse_non(&non, "<test>").unwrap();
+        assert_eq!(parsed.title, puzzle_config.title);
+        assert_eq!(parsed.by, puzzle_config.by);
+        assert_eq!(parsed.source, puzzle_config.source);
+        assert_eq!(parsed.rows, puzzle_config.rows);
+        assert_eq!(parsed.columns, puzzle_config.columns);
+    }
+}
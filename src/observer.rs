@@ -0,0 +1,73 @@
+use crate::cell::Cell;
+use crate::line::Line;
+use crate::puzzle::SolveStats;
+
+/// Receives progress events while a puzzle is being solved. Every method does nothing by default,
+/// so an observer only needs to implement the events it is interested in.
+pub trait SolveObserver {
+    /// Called at the start of each iteration of line logic on the puzzle.
+    fn iteration_started(&mut self, _iteration: u32, _potential_solutions_remaining: u32) {}
+
+    /// Called after a line has been solved and its cells copied into the crossing lines,
+    /// with the indices of the cells which that solve changed.
+    fn line_solved(&mut self, _line: &Line, _cells_changed: &[u8]) {}
+
+    /// Called after a value has been tried in an unknown cell on a copy of the puzzle.
+    fn cell_probed(&mut self, _row: u8, _col: u8, _guess: &Cell, _led_to_contradiction: bool) {}
+
+    /// Called when the puzzle itself turns out to be contradictory, with the first line found to be at fault.
+    fn contradiction(&mut self, _line: &Line) {}
+
+    /// Called once solving has finished, whether or not the puzzle was solved.
+    fn completed(&mut self, _stats: &SolveStats) {}
+}
+
+/// An observer which ignores every event.
+pub struct NullObserver;
+
+impl SolveObserver for NullObserver {}
+
+/// Reports solver progress through the `log` crate.
+pub struct LoggingObserver;
+
+impl SolveObserver for LoggingObserver {
+    fn iteration_started(&mut self, iteration: u32, potential_solutions_remaining: u32) {
+        log::debug!(
+            "Starting iteration {iteration}, with {potential_solutions_remaining} total potential individual line solutions remaining"
+        );
+    }
+
+    fn line_solved(&mut self, line: &Line, cells_changed: &[u8]) {
+        if !cells_changed.is_empty() {
+            log::debug!(
+                "{} decided {} cells, {} potential solutions remaining",
+                line.label(),
+                cells_changed.len(),
+                line.potential_solution_count()
+            );
+        }
+    }
+
+    fn cell_probed(&mut self, row: u8, col: u8, guess: &Cell, led_to_contradiction: bool) {
+        if led_to_contradiction {
+            log::debug!(
+                "Probing Row {} Col {} as {:?} led to a contradiction",
+                row + 1,
+                col + 1,
+                guess
+            );
+        }
+    }
+
+    fn contradiction(&mut self, line: &Line) {
+        log::warn!("The puzzle is contradictory: {} cannot be solved", line.label());
+    }
+
+    fn completed(&mut self, stats: &SolveStats) {
+        log::debug!(
+            "Solving finished after {} iterations and {} probes",
+            stats.iterations,
+            stats.probes
+        );
+    }
+}
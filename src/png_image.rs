@@ -0,0 +1,65 @@
+use crate::cell::Cell;
+use crate::puzzle::Puzzle;
+use std::io::Write;
+
+/// An RGB colour.
+pub type Colour = [u8; 3];
+
+/// Options for drawing a puzzle's cells as a PNG image.
+#[derive(Debug, Clone)]
+pub struct PngOptions {
+    /// The width and height of each cell, in pixels.
+    pub cell_size: u32,
+    pub block_colour: Colour,
+    pub space_colour: Colour,
+    pub unknown_colour: Colour,
+    /// The colour of one pixel wide lines drawn around every cell, or `None` for no lines.
+    pub grid_line_colour: Option<Colour>,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        PngOptions {
+            cell_size: 10,
+            block_colour: [0, 0, 0],
+            space_colour: [255, 255, 255],
+            unknown_colour: [192, 192, 192],
+            grid_line_colour: None,
+        }
+    }
+}
+
+impl Puzzle {
+    /// Draws the puzzle's cells, solved or not, as a PNG image with one square per cell.
+    pub fn write_png<W: Write>(&self, writer: W, options: &PngOptions) -> std::io::Result<()> {
+        let cell_size = options.cell_size as usize;
+        let line_width = usize::from(options.grid_line_colour.is_some());
+        let width = self.col_count() as usize * (cell_size + line_width) + line_width;
+        let height = self.row_count() as usize * (cell_size + line_width) + line_width;
+        // Start with every pixel in the grid line colour, and then paint each cell inside its lines.
+        let background = options.grid_line_colour.unwrap_or(options.space_colour);
+        let mut pixels: Vec<u8> = background.repeat(width * height);
+        for (row, cells) in self.grid().iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                let colour = match cell {
+                    Cell::Block => options.block_colour,
+                    Cell::Space => options.space_colour,
+                    Cell::Unknown => options.unknown_colour,
+                };
+                let left = col * (cell_size + line_width) + line_width;
+                let top = row * (cell_size + line_width) + line_width;
+                for y in top..top + cell_size {
+                    let start = (y * width + left) * 3;
+                    pixels[start..start + cell_size * 3].copy_from_slice(&colour.repeat(cell_size));
+                }
+            }
+        }
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header()?;
+        png_writer.write_image_data(&pixels)?;
+        png_writer.finish()?;
+        Ok(())
+    }
+}
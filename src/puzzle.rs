@@ -1,8 +1,12 @@
+use crate::cancellation::CancellationToken;
 use crate::cell::Cell;
+use crate::error::{PuzzleError, Result};
 use crate::line::{Line, LineType};
-use colored::Colorize;
+use crate::observer::{LoggingObserver, NullObserver, SolveObserver};
+use crate::render::RenderOptions;
+use rayon::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Puzzle {
     pub title: String,
     pub author: String,
@@ -10,49 +14,249 @@ pub struct Puzzle {
     pub source: String,
     pub row_clues: Vec<Vec<u8>>,
     pub col_clues: Vec<Vec<u8>>,
-    pub rows: Vec<Line>,
-    pub cols: Vec<Line>,
-    pub padding: u8,
+    /// The cells known before solving starts, one row at a time, if the puzzle file gave any.
+    pub givens: Option<Vec<Vec<Cell>>>,
+    /// The puzzle's known solution, one row at a time, if the puzzle file gave one.
+    pub solution: Option<Vec<Vec<Cell>>>,
+    pub(crate) rows: Vec<Line>,
+    pub(crate) cols: Vec<Line>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    pub max_iterations: u32,
+    /// Number of threads used to solve lines concurrently: 1 solves sequentially, 0 uses one thread per CPU core.
+    pub threads: usize,
+    /// Whether to probe individual cells when line logic alone makes no more progress.
+    pub probing: bool,
+    /// Checked between line solves; once cancelled, solving stops and leaves the puzzle partially solved.
+    pub cancellation: CancellationToken,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            max_iterations: 100,
+            threads: 1,
+            probing: true,
+            cancellation: CancellationToken::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Line logic iterations used on the puzzle itself.
+    pub iterations: u32,
+    /// Number of times a value was tried in an unknown cell.
+    pub probes: u32,
+    /// Line logic iterations used on the copies of the puzzle made while probing.
+    pub probe_iterations: u32,
+    pub cells_fixed_by_probing: u32,
+    pub solved: bool,
+    /// Whether solving was stopped early through the cancellation token.
+    pub cancelled: bool,
+    /// Whether solving stopped because `max_iterations` was used up.
+    pub limit_reached: bool,
+    /// If the puzzle turned out to have no solution, the label of the first line found to be at fault.
+    pub contradiction: Option<String>,
+}
+
+impl SolveStats {
+    /// Whether the puzzle was solved, or the reason why it wasn't.
+    pub fn result(&self) -> Result<()> {
+        if self.solved {
+            Ok(())
+        } else if let Some(line) = &self.contradiction {
+            Err(PuzzleError::Contradiction { line: line.clone() })
+        } else if self.cancelled || self.limit_reached {
+            Err(PuzzleError::LimitReached {
+                iterations: self.iterations,
+                cancelled: self.cancelled,
+            })
+        } else {
+            Err(PuzzleError::Unsolved { iterations: self.iterations })
+        }
+    }
 }
 
 impl Puzzle {
-    pub fn solve(&mut self, max_iterations: u32) -> (u32, bool) {
+    /// Solves the puzzle using line logic, falling back to probing individual cells whenever line logic stalls.
+    /// Progress is reported through the `log` crate.
+    pub fn solve(&mut self, options: &SolveOptions) -> SolveStats {
+        self.solve_with_observer(options, &mut LoggingObserver)
+    }
+
+    /// Solves the puzzle as `solve` does, reporting progress to the given observer.
+    pub fn solve_with_observer(
+        &mut self,
+        options: &SolveOptions,
+        observer: &mut dyn SolveObserver,
+    ) -> SolveStats {
+        let thread_pool = build_thread_pool(options.threads);
+        let mut stats = SolveStats::default();
+        loop {
+            let (iterations, puzzle_is_solved) = self.solve_with_line_logic(
+                options.max_iterations - stats.iterations,
+                thread_pool.as_ref(),
+                &options.cancellation,
+                observer,
+            );
+            stats.iterations += iterations;
+            if let Some(line) = self.find_contradiction() {
+                observer.contradiction(line);
+                stats.contradiction = Some(line.label());
+                break;
+            }
+            stats.solved = puzzle_is_solved;
+            if puzzle_is_solved
+                || !options.probing
+                || stats.iterations >= options.max_iterations
+                || options.cancellation.is_cancelled()
+            {
+                break;
+            }
+            if !self.probe(options, thread_pool.as_ref(), &mut stats, observer) {
+                break;
+            }
+        }
+        let stopped_early = !stats.solved && stats.contradiction.is_none();
+        stats.cancelled = stopped_early && options.cancellation.is_cancelled();
+        stats.limit_reached = stopped_early && stats.iterations >= options.max_iterations;
+        observer.completed(&stats);
+        stats
+    }
+
+    /// Repeatedly solves every row and then every column until no more progress can be made.
+    /// With a thread pool, all rows, and then all columns, are solved concurrently; the result is
+    /// identical to the sequential solver.
+    fn solve_with_line_logic(
+        &mut self,
+        max_iterations: u32,
+        thread_pool: Option<&rayon::ThreadPool>,
+        cancellation: &CancellationToken,
+        observer: &mut dyn SolveObserver,
+    ) -> (u32, bool) {
         let mut iterations: u32 = 0; //plenty of room for iterations :-)
         let mut progress_was_made = true;
         let mut puzzle_is_solved = false;
-        while progress_was_made && iterations < max_iterations {
-            puzzle_is_solved = true;
-            progress_was_made = false;
+        while progress_was_made && iterations < max_iterations && !cancellation.is_cancelled() {
             iterations += 1;
-            log::debug!(
-                "Starting iteration {}, with {} total potential individual line solutions remaining",
-                iterations,
-                self.total_potential_solutions_remaining()
-            );
-            for i in 0..self.row_count() {
-                let mut line = self.get_line(LineType::Row, i);
-                let (line_is_solved, progress) = line.solve();
-                if !line_is_solved {
-                    puzzle_is_solved = false;
+            observer.iteration_started(iterations, self.total_potential_solutions_remaining());
+            let (rows_are_solved, row_progress) =
+                self.solve_all_lines(LineType::Row, thread_pool, cancellation, observer);
+            let (cols_are_solved, col_progress) =
+                self.solve_all_lines(LineType::Col, thread_pool, cancellation, observer);
+            puzzle_is_solved = rows_are_solved && cols_are_solved;
+            progress_was_made = row_progress || col_progress;
+        }
+        (iterations, puzzle_is_solved)
+    }
+
+    /// Tries each value in each unknown cell on a copy of the puzzle, and runs line logic on the copy.
+    /// If that leads to a contradiction, the cell must hold the opposite value, so it is set in this puzzle.
+    /// Returns true as soon as a cell has been fixed, so that the cheaper line logic can take over again.
+    pub(crate) fn probe(
+        &mut self,
+        options: &SolveOptions,
+        thread_pool: Option<&rayon::ThreadPool>,
+        stats: &mut SolveStats,
+        observer: &mut dyn SolveObserver,
+    ) -> bool {
+        for row in 0..self.row_count() {
+            for col in 0..self.col_count() {
+                if self.rows[row as usize].get_cell(col) != Cell::Unknown {
+                    continue;
                 }
-                if progress {
-                    progress_was_made = true;
+                for (guess, opposite) in [(Cell::Block, Cell::Space), (Cell::Space, Cell::Block)] {
+                    if options.cancellation.is_cancelled() {
+                        return false;
+                    }
+                    stats.probes += 1;
+                    let mut probe = self.clone();
+                    probe.set_cell(row, col, guess.clone());
+                    let (iterations, _) = probe.solve_with_line_logic(
+                        options.max_iterations,
+                        thread_pool,
+                        &options.cancellation,
+                        &mut NullObserver,
+                    );
+                    stats.probe_iterations += iterations;
+                    // A probe cut short by cancellation may not have reached its contradiction yet,
+                    // but any contradiction it did find is still sound.
+                    let led_to_contradiction = probe.has_contradiction();
+                    observer.cell_probed(row, col, &guess, led_to_contradiction);
+                    if led_to_contradiction {
+                        self.set_cell(row, col, opposite);
+                        stats.cells_fixed_by_probing += 1;
+                        return true;
+                    }
                 }
-                self.set_line(line);
             }
-            for i in 0..self.col_count() {
-                let mut line = self.get_line(LineType::Col, i);
-                let (line_is_solved, progress) = line.solve();
-                if !line_is_solved {
-                    puzzle_is_solved = false;
-                }
-                if progress {
-                    progress_was_made = true;
-                }
-                self.set_line(line);
+        }
+        false
+    }
+
+    pub fn has_contradiction(&self) -> bool {
+        self.find_contradiction().is_some()
+    }
+
+    /// The first row or column, if any, which can no longer be solved.
+    pub fn find_contradiction(&self) -> Option<&Line> {
+        self.rows
+            .iter()
+            .chain(self.cols.iter())
+            .find(|line| line.has_contradiction())
+    }
+
+    /// Solves every line along one axis, then copies the cells each line decided into the crossing lines.
+    /// Lines along the same axis never share cells, so they can be solved in any order, or all at once.
+    fn solve_all_lines(
+        &mut self,
+        axis: LineType,
+        thread_pool: Option<&rayon::ThreadPool>,
+        cancellation: &CancellationToken,
+        observer: &mut dyn SolveObserver,
+    ) -> (bool, bool) {
+        let lines = match axis {
+            LineType::Row => &mut self.rows,
+            LineType::Col => &mut self.cols,
+        };
+        // Once cancelled, the remaining lines are left as they are, which keeps the puzzle consistent.
+        let solve_line = |line: &mut Line| {
+            if cancellation.is_cancelled() {
+                return (line.is_solved(), false, Vec::new());
+            }
+            let cells_before = line.cells.clone();
+            let (line_is_solved, progress) = line.solve();
+            let cells_changed: Vec<u8> = (0..line.length())
+                .filter(|&i| line.cells[i as usize] != cells_before[i as usize])
+                .collect();
+            (line_is_solved, progress, cells_changed)
+        };
+        let results: Vec<(bool, bool, Vec<u8>)> = match thread_pool {
+            Some(thread_pool) => {
+                thread_pool.install(|| lines.par_iter_mut().map(solve_line).collect())
+            }
+            None => lines.iter_mut().map(solve_line).collect(),
+        };
+        let mut lines_are_solved = true;
+        let mut progress_was_made = false;
+        for (i, (line_is_solved, progress, cells_changed)) in results.into_iter().enumerate() {
+            if !line_is_solved {
+                lines_are_solved = false;
             }
+            if progress {
+                progress_was_made = true;
+            }
+            self.copy_cells_to_crossing_lines(axis, i as u8);
+            let line = match axis {
+                LineType::Row => &self.rows[i],
+                LineType::Col => &self.cols[i],
+            };
+            observer.line_solved(line, &cells_changed);
         }
-        (iterations, puzzle_is_solved)
+        (lines_are_solved, progress_was_made)
     }
 
     pub fn get_line(&self, axis: LineType, index: u8) -> Line {
@@ -73,47 +277,41 @@ impl Puzzle {
         total
     }
 
-    pub fn set_line(&mut self, line: Line) {
-        match line.axis {
-            LineType::Row => {
-                self.rows[line.index as usize] = line.clone();
-                for (i, cell) in line.cells.iter().enumerate() {
-                    match cell {
-                        Cell::Block => {
-                            let mut col_line = self.cols[i].clone();
-                            col_line.set_cell(line.index, Cell::Block);
-                            self.cols[i] = col_line.clone();
-                        }
-                        Cell::Space => {
-                            let mut col_line = self.cols[i].clone();
-                            col_line.set_cell(line.index, Cell::Space);
-                            self.cols[i] = col_line.clone();
-                        }
-                        _ => (),
-                    }
-                }
-            }
-            LineType::Col => {
-                self.cols[line.index as usize] = line.clone();
-                for (i, cell) in line.cells.iter().enumerate() {
-                    match cell {
-                        Cell::Block => {
-                            let mut row_line = self.rows[i].clone();
-                            row_line.set_cell(line.index, Cell::Block);
-                            self.rows[i] = row_line.clone();
-                        }
-                        Cell::Space => {
-                            let mut row_line = self.rows[i].clone();
-                            row_line.set_cell(line.index, Cell::Space);
-                            self.rows[i] = row_line.clone();
-                        }
-                        _ => (),
-                    }
-                }
+    fn copy_cells_to_crossing_lines(&mut self, axis: LineType, index: u8) {
+        let (line, crossing_lines) = match axis {
+            LineType::Row => (&self.rows[index as usize], &mut self.cols),
+            LineType::Col => (&self.cols[index as usize], &mut self.rows),
+        };
+        for (i, cell) in line.cells.iter().enumerate() {
+            match cell {
+                Cell::Block | Cell::Space => crossing_lines[i].set_cell(index, cell.clone()),
+                _ => (),
             }
         }
     }
 
+    pub fn set_cell(&mut self, row: u8, col: u8, cell: Cell) {
+        self.rows[row as usize].set_cell(col, cell.clone());
+        self.cols[col as usize].set_cell(row, cell);
+    }
+
+    pub fn rows(&self) -> &[Line] {
+        &self.rows
+    }
+
+    pub fn cols(&self) -> &[Line] {
+        &self.cols
+    }
+
+    pub fn cell(&self, row: u8, col: u8) -> Cell {
+        self.rows[row as usize].get_cell(col)
+    }
+
+    /// The current state of every cell, one row at a time.
+    pub fn grid(&self) -> Vec<Vec<Cell>> {
+        self.rows.iter().map(|line| line.cells.clone()).collect()
+    }
+
     pub fn row_count(&self) -> u8 {
         self.rows.len() as u8
     }
@@ -122,51 +320,21 @@ impl Puzzle {
         self.cols.len() as u8
     }
 
-    fn max_col_clue_length(&self) -> u8 {
-        let mut max_length = 0;
-
-        for clue in &self.col_clues {
-            if clue.len() > max_length {
-                max_length = clue.len();
-            }
-        }
-        max_length as u8
-    }
-
+    /// Prints the puzzle, as drawn by `render` with the default options.
     pub fn dump(&self) {
-        println!("\nTitle: {}", self.title);
-        if !self.author.is_empty() {
-            println!("Author: {}", self.author);
-        }
+        print!("{}", self.render(&RenderOptions::default()));
+    }
+}
 
-        for i in 0..(self.max_col_clue_length()) {
-            let mut display_col_clues = String::new();
-            for clue in self.col_clues.iter() {
-                if clue.len() as u8 > i {
-                    display_col_clues += &format!(
-                        "{:>width$} ",
-                        clue[i as usize],
-                        width = self.padding as usize
-                    );
-                } else {
-                    display_col_clues += &format!("{:>width$} ", "", width = self.padding as usize);
-                }
-            }
-            print!("{}", display_col_clues.yellow());
-            println!();
-        }
-        let rows = &self.rows.clone();
-        for (line_index, line) in rows.iter().enumerate() {
-            let mut display_cells = String::new();
-            display_cells += " ";
-            for cell in line.cells.iter() {
-                display_cells +=
-                    &format!("{:>width$} ", cell.display(), width = self.padding as usize);
-            }
-            print!("{}", display_cells.blue());
-            display_cells = format!(" {}", clue_as_string(&self.row_clues[line_index]));
-            print!("{}", display_cells.yellow());
-            println!();
+fn build_thread_pool(threads: usize) -> Option<rayon::ThreadPool> {
+    if threads == 1 {
+        return None;
+    }
+    match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(thread_pool) => Some(thread_pool),
+        Err(err) => {
+            log::warn!("Couldn't start thread pool, solving sequentially: {err}");
+            None
         }
     }
 }
@@ -181,3 +349,67 @@ pub fn clue_as_string(clue: &[u8]) -> String {
     }
     clue_string
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::cells_from_text;
+    use crate::puzzle_builder::PuzzleBuilder;
+
+    /// A puzzle on which line logic stalls with cells still unknown, but which probing solves.
+    fn stalls_without_probing() -> Puzzle {
+        PuzzleBuilder::new(
+            vec![vec![2], vec![2, 1], vec![2], vec![1, 1], vec![1]],
+            vec![vec![1], vec![3], vec![2], vec![1], vec![1, 2]],
+        )
+        .build()
+        .unwrap()
+    }
+
+    fn unknown_cells(puzzle: &Puzzle) -> usize {
+        puzzle.grid().iter().flatten().filter(|cell| **cell == Cell::Unknown).count()
+    }
+
+    #[test]
+    fn line_logic_alone_stalls() {
+        let mut puzzle = stalls_without_probing();
+        let options = SolveOptions {
+            probing: false,
+            ..SolveOptions::default()
+        };
+        let stats = puzzle.solve_with_observer(&options, &mut NullObserver);
+        assert!(!stats.solved);
+        assert!(!stats.limit_reached);
+        assert_eq!(stats.probes, 0);
+        assert!(unknown_cells(&puzzle) > 0);
+    }
+
+    #[test]
+    fn probe_fixes_a_cell_once_line_logic_stalls() {
+        let mut puzzle = stalls_without_probing();
+        let options = SolveOptions {
+            probing: false,
+            ..SolveOptions::default()
+        };
+        puzzle.solve_with_observer(&options, &mut NullObserver);
+        let unknown_before = unknown_cells(&puzzle);
+        let mut stats = SolveStats::default();
+        assert!(puzzle.probe(&options, None, &mut stats, &mut NullObserver));
+        assert_eq!(stats.cells_fixed_by_probing, 1);
+        assert_eq!(unknown_cells(&puzzle), unknown_before - 1);
+        assert!(!puzzle.has_contradiction());
+    }
+
+    #[test]
+    fn probing_solves_when_line_logic_stalls() {
+        let mut puzzle = stalls_without_probing();
+        let stats = puzzle.solve_with_observer(&SolveOptions::default(), &mut NullObserver);
+        assert!(stats.solved);
+        assert!(stats.cells_fixed_by_probing > 0);
+        let expected: Vec<Vec<Cell>> = ["..##.", ".##.#", "##...", ".#..#", "....#"]
+            .iter()
+            .map(|row| cells_from_text(row).unwrap())
+            .collect();
+        assert_eq!(puzzle.grid(), expected);
+    }
+}
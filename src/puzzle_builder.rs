@@ -0,0 +1,289 @@
+use crate::cell::Cell;
+use crate::error::{IN_MEMORY_SOURCE, PuzzleError, Result};
+use crate::line::{Line, LineType};
+use crate::line_algorithms::clues_from_grid;
+use crate::puzzle::Puzzle;
+
+// Line lengths and indices are held in a u8.
+const MAX_LINE_LENGTH: usize = u8::MAX as usize;
+
+/// Builds a `Puzzle` from its clues, with optional metadata and starting cells.
+///
+/// ```
+/// use nonorust::{Cell, PuzzleBuilder};
+///
+/// let puzzle = PuzzleBuilder::new(vec![vec![1], vec![2]], vec![vec![2], vec![1]])
+///     .title("Corner")
+///     .starting_cell(0, 0, Cell::Block)
+///     .build()
+///     .unwrap();
+/// assert_eq!(puzzle.row_count(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PuzzleBuilder {
+    title: String,
+    author: String,
+    license: String,
+    source: String,
+    row_clues: Vec<Vec<u8>>,
+    col_clues: Vec<Vec<u8>>,
+    starting_cells: Vec<(u8, u8, Cell)>,
+    givens: Option<Vec<Vec<Cell>>>,
+    solution: Option<Vec<Vec<Cell>>>,
+}
+
+impl PuzzleBuilder {
+    pub fn new(row_clues: Vec<Vec<u8>>, col_clues: Vec<Vec<u8>>) -> PuzzleBuilder {
+        PuzzleBuilder {
+            row_clues,
+            col_clues,
+            ..PuzzleBuilder::default()
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> PuzzleBuilder {
+        self.title = title.into();
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<String>) -> PuzzleBuilder {
+        self.author = author.into();
+        self
+    }
+
+    pub fn license(mut self, license: impl Into<String>) -> PuzzleBuilder {
+        self.license = license.into();
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> PuzzleBuilder {
+        self.source = source.into();
+        self
+    }
+
+    /// Marks a cell as already known before solving starts. Unknown cells are ignored.
+    pub fn starting_cell(mut self, row: u8, col: u8, cell: Cell) -> PuzzleBuilder {
+        if cell != Cell::Unknown {
+            self.starting_cells.push((row, col, cell));
+        }
+        self
+    }
+
+    /// Marks every known cell of a grid, given one row at a time, as already known before solving starts.
+    pub fn starting_grid(mut self, grid: &[Vec<Cell>]) -> PuzzleBuilder {
+        for (row, cells) in grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                self = self.starting_cell(row as u8, col as u8, cell.clone());
+            }
+        }
+        self
+    }
+
+    /// The puzzle's givens, one row at a time: cells known before solving starts, which unlike other starting
+    /// cells are kept as part of the puzzle when it is saved or transformed.
+    pub fn givens(mut self, givens: Vec<Vec<Cell>>) -> PuzzleBuilder {
+        self = self.starting_grid(&givens);
+        self.givens = Some(givens);
+        self
+    }
+
+    /// The puzzle's known solution, one row at a time, which the solver's result can be checked against.
+    pub fn solution(mut self, solution: Vec<Vec<Cell>>) -> PuzzleBuilder {
+        self.solution = Some(solution);
+        self
+    }
+
+    pub fn build(self) -> Result<Puzzle> {
+        self.build_from(IN_MEMORY_SOURCE)
+    }
+
+    /// Builds the puzzle, using `source_name` in place of a file path in any validation error.
+    pub(crate) fn build_from(mut self, source_name: &str) -> Result<Puzzle> {
+        self.validate(source_name)?;
+        let row_count = self.row_clues.len() as u8;
+        let col_count = self.col_clues.len() as u8;
+        let mut rows = Vec::new();
+        log::debug!("Generating row lines...");
+        for i in 0..row_count {
+            let line = Line::new(
+                LineType::Row,
+                i,
+                vec![Cell::Unknown; col_count as usize],
+                self.row_clues[i as usize].clone(),
+            );
+            log::debug!(
+                "Generated {} which has {} potential solutions",
+                line.label(),
+                line.potential_solutions.len()
+            );
+            rows.push(line);
+        }
+        log::debug!("Generating col lines...");
+        let mut cols = Vec::new();
+        for i in 0..col_count {
+            let line = Line::new(
+                LineType::Col,
+                i,
+                vec![Cell::Unknown; row_count as usize],
+                self.col_clues[i as usize].clone(),
+            );
+            log::debug!(
+                "Generated {} which has {} potential solutions",
+                line.label(),
+                line.potential_solutions.len()
+            );
+            cols.push(line)
+        }
+        log::debug!("Row & col lines generated OK");
+        let mut puzzle = Puzzle {
+            title: self.title,
+            author: self.author,
+            license: self.license,
+            source: self.source,
+            row_clues: self.row_clues,
+            col_clues: self.col_clues,
+            givens: self.givens,
+            solution: self.solution,
+            rows,
+            cols,
+        };
+        apply_starting_cells(&mut puzzle, &self.starting_cells, source_name)?;
+        Ok(puzzle)
+    }
+
+    /// Checks that the clues describe a puzzle which could have a solution. A clue of `[0]` is accepted
+    /// as another way of writing an empty clue, and is normalised to `[]`.
+    fn validate(&mut self, source_name: &str) -> Result<()> {
+        if self.row_clues.is_empty() || self.col_clues.is_empty() {
+            return Err(PuzzleError::validation(
+                source_name,
+                String::from("the puzzle must have at least one row and one column"),
+            ));
+        }
+        if self.row_clues.len() > MAX_LINE_LENGTH || self.col_clues.len() > MAX_LINE_LENGTH {
+            return Err(PuzzleError::validation(
+                source_name,
+                format!("the puzzle can have at most {MAX_LINE_LENGTH} rows and {MAX_LINE_LENGTH} columns"),
+            ));
+        }
+        let row_length = self.col_clues.len();
+        let col_length = self.row_clues.len();
+        for (i, clue) in self.row_clues.iter_mut().enumerate() {
+            validate_clue(source_name, &format!("Row {}", i + 1), clue, row_length)?;
+        }
+        for (i, clue) in self.col_clues.iter_mut().enumerate() {
+            validate_clue(source_name, &format!("Col {}", i + 1), clue, col_length)?;
+        }
+        let row_blocks: usize = self.row_clues.iter().flatten().map(|&n| n as usize).sum();
+        let col_blocks: usize = self.col_clues.iter().flatten().map(|&n| n as usize).sum();
+        if row_blocks != col_blocks {
+            return Err(PuzzleError::validation(
+                source_name,
+                format!("the row clues add up to {row_blocks} blocks, but the column clues add up to {col_blocks}"),
+            ));
+        }
+        for (row, col, _) in &self.starting_cells {
+            if *row as usize >= col_length || *col as usize >= row_length {
+                return Err(PuzzleError::validation(
+                    source_name,
+                    format!("starting cell Row {} Col {} is outside the grid", row + 1, col + 1),
+                ));
+            }
+        }
+        if let Some(givens) = &self.givens
+            && (givens.len() != col_length || givens.iter().any(|row| row.len() != row_length))
+        {
+            return Err(PuzzleError::validation(
+                source_name,
+                format!("the givens must have {col_length} rows of {row_length} cells"),
+            ));
+        }
+        if let Some(solution) = &self.solution {
+            validate_solution(source_name, solution, &self.row_clues, &self.col_clues)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_clue(source_name: &str, label: &str, clue: &mut Vec<u8>, length: usize) -> Result<()> {
+    if clue.as_slice() == [0] {
+        clue.clear();
+    }
+    if clue.contains(&0) {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("{label} has a zero in its clue {clue:?}"),
+        ));
+    }
+    let minimum_length = clue.iter().map(|&n| n as usize).sum::<usize>() + clue.len().saturating_sub(1);
+    if minimum_length > length {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("{label} clue {clue:?} needs at least {minimum_length} cells, but the line only has {length}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a solution is complete, and that it fits every row and column clue.
+fn validate_solution(
+    source_name: &str,
+    solution: &[Vec<Cell>],
+    row_clues: &[Vec<u8>],
+    col_clues: &[Vec<u8>],
+) -> Result<()> {
+    if solution.len() != row_clues.len() || solution.iter().any(|row| row.len() != col_clues.len()) {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("the solution must have {} rows of {} cells", row_clues.len(), col_clues.len()),
+        ));
+    }
+    if solution.iter().flatten().any(|cell| *cell == Cell::Unknown) {
+        return Err(PuzzleError::validation(
+            source_name,
+            String::from("the solution must not have any unknown cells"),
+        ));
+    }
+    let (solution_row_clues, solution_col_clues) = clues_from_grid(solution);
+    let lines = [("Row", row_clues, solution_row_clues), ("Col", col_clues, solution_col_clues)];
+    for (axis, clues, solution_clues) in lines {
+        for (i, (clue, solution_clue)) in clues.iter().zip(solution_clues).enumerate() {
+            if *clue != solution_clue {
+                return Err(PuzzleError::validation(
+                    source_name,
+                    format!("the solution doesn't fit the clue {clue:?} of {axis} {}", i + 1),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sets the starting cells in the rows and columns, and removes every potential solution which doesn't fit them.
+fn apply_starting_cells(
+    puzzle: &mut Puzzle,
+    starting_cells: &[(u8, u8, Cell)],
+    source_name: &str,
+) -> Result<()> {
+    if starting_cells.is_empty() {
+        return Ok(());
+    }
+    for (row, col, cell) in starting_cells {
+        puzzle.set_cell(*row, *col, cell.clone());
+    }
+    for line in puzzle.rows.iter_mut().chain(puzzle.cols.iter_mut()) {
+        if line.has_contradiction() {
+            return Err(PuzzleError::validation(
+                source_name,
+                format!("the starting cells in {} don't fit its clue {:?}", line.label(), line.clue),
+            ));
+        }
+        line.remove_solutions_which_do_not_fit_known_cells();
+    }
+    log::debug!(
+        "Applied {} starting cells, leaving {} potential solutions",
+        starting_cells.len(),
+        puzzle.total_potential_solutions_remaining()
+    );
+    Ok(())
+}
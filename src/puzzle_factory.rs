@@ -1,105 +1,295 @@
-use crate::cell::Cell;
-use crate::line::{Line, LineType};
+use crate::cell::{Cell, cells_from_text, cells_to_text};
+use crate::error::{IN_MEMORY_SOURCE, PuzzleError, Result};
+use crate::non_format::{parse_non, write_non};
 use crate::puzzle::Puzzle;
+use crate::puzzle_builder::PuzzleBuilder;
 use serde::{Deserialize, Serialize};
-use std::error;
 
-type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
-
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PuzzleConfig {
     pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
     pub rows: Vec<Vec<u8>>,
     pub columns: Vec<Vec<u8>>,
+    /// Cells known before solving starts, one string per row, using `#` for blocks, `.` for spaces and `?`
+    /// for the cells which are left to solve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub givens: Option<Vec<String>>,
+    /// The puzzle's solution, in the same form as the givens but with no unknown cells.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution: Option<Vec<String>>,
+}
+
+/// The puzzle file formats which can be read and written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleFormat {
+    /// This project's own YAML format, as used by the files in `data`.
+    Yaml,
+    /// The `.non` format used by nonogram-db.
+    Non,
 }
 
+impl PuzzleFormat {
+    /// Guesses the format of a puzzle file from its extension, defaulting to YAML.
+    pub fn from_path(file_path: &str) -> PuzzleFormat {
+        if file_path.ends_with(".non") {
+            PuzzleFormat::Non
+        } else {
+            PuzzleFormat::Yaml
+        }
+    }
+}
+
+// Used in place of the file path `-` when reporting errors in puzzles read from standard input.
+const STDIN_SOURCE: &str = "<stdin>";
+
 impl PuzzleConfig {
+    /// Loads a puzzle from a file, or from standard input if `file_path` is `-`.
     pub fn build(file_path: String) -> Result<Puzzle> {
-        let path = std::path::Path::new(&file_path);
-        let display = path.display();
+        let puzzle_config = PuzzleConfig::read(&file_path)?;
+        puzzle_config.into_puzzle_from(source_name(&file_path))
+    }
+
+    /// Reads a puzzle file without building the puzzle. Files ending in `.non` are read in the nonogram-db
+    /// format, and anything else, including standard input when `file_path` is `-`, as YAML.
+    pub fn read(file_path: &str) -> Result<PuzzleConfig> {
+        if file_path == "-" {
+            return PuzzleConfig::parse(std::io::stdin().lock(), PuzzleFormat::Yaml, STDIN_SOURCE);
+        }
+        let path = std::path::Path::new(file_path);
         let file = match std::fs::File::open(path) {
             Ok(file) => {
                 log::debug!("Opened file OK");
                 file
             }
             Err(err) => {
-                eprintln!("Couldn't open {display}: {err}");
-                return Err(err.into());
+                return Err(PuzzleError::Io {
+                    path: file_path.to_string(),
+                    source: err,
+                });
             }
         };
-        let deserialized_puzzle: PuzzleConfig = match serde_yaml::from_reader(file) {
-            Ok(puzzle_config) => {
-                log::debug!("Loaded puzzle from file OK");
-                puzzle_config
-            }
-            Err(err) => {
-                eprintln!("Error deserializing YAML: {err}");
-                return Err(err.into());
+        PuzzleConfig::parse(file, PuzzleFormat::from_path(file_path), file_path)
+    }
+
+    /// Loads a puzzle from a string holding the YAML puzzle format.
+    // Not `FromStr`, since this builds a `Puzzle` rather than a `PuzzleConfig`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(yaml: &str) -> Result<Puzzle> {
+        let deserialized_puzzle: PuzzleConfig = match serde_yaml::from_str(yaml) {
+            Ok(puzzle_config) => puzzle_config,
+            Err(err) => return Err(PuzzleError::from_yaml(IN_MEMORY_SOURCE, err)),
+        };
+        deserialized_puzzle.into_puzzle_from(IN_MEMORY_SOURCE)
+    }
+
+    /// Loads a puzzle in the YAML puzzle format from any reader.
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Puzzle> {
+        PuzzleConfig::parse(reader, PuzzleFormat::Yaml, IN_MEMORY_SOURCE)?.into_puzzle_from(IN_MEMORY_SOURCE)
+    }
+
+    /// Parses a puzzle in the given format. `source_name` is only used in error messages.
+    pub fn parse<R: std::io::Read>(
+        mut reader: R,
+        format: PuzzleFormat,
+        source_name: &str,
+    ) -> Result<PuzzleConfig> {
+        let deserialized_puzzle = match format {
+            PuzzleFormat::Yaml => match serde_yaml::from_reader(reader) {
+                Ok(puzzle_config) => puzzle_config,
+                Err(err) => return Err(PuzzleError::from_yaml(source_name, err)),
+            },
+            PuzzleFormat::Non => {
+                let mut text = String::new();
+                if let Err(err) = reader.read_to_string(&mut text) {
+                    return Err(PuzzleError::Io {
+                        path: source_name.to_string(),
+                        source: err,
+                    });
+                }
+                parse_non(&text, source_name)?
             }
         };
-        let row_count = deserialized_puzzle.rows.len() as u8;
-        let col_count = deserialized_puzzle.columns.len() as u8;
-        let row_clues = deserialized_puzzle.rows.clone();
-        let col_clues = deserialized_puzzle.columns.clone();
-        let mut rows = Vec::new();
-        log::debug!("Generating row lines...");
-        for i in 0..row_count {
-            let line = Line::new(
-                LineType::Row,
-                i,
-                vec![Cell::Unknown; col_count as usize],
-                row_clues[i as usize].clone(),
-            );
-            log::debug!(
-                "Generated {} which has {} potential solutions",
-                line.label(),
-                line.potential_solutions.len()
-            );
-            rows.push(line);
+        log::debug!("Loaded puzzle from {source_name} OK");
+        Ok(deserialized_puzzle)
+    }
+
+    /// Writes the puzzle in the given format. The `.non` format has no way to give cells, so a puzzle with
+    /// givens can only be written as YAML.
+    pub fn to_string_as(&self, format: PuzzleFormat) -> Result<String> {
+        match format {
+            PuzzleFormat::Yaml => Ok(write_yaml(self)),
+            PuzzleFormat::Non if self.givens.is_some() => Err(PuzzleError::validation(
+                IN_MEMORY_SOURCE,
+                String::from("a puzzle with givens can't be written in the .non format"),
+            )),
+            PuzzleFormat::Non => Ok(write_non(self)),
+        }
+    }
+
+    pub fn into_puzzle(self) -> Result<Puzzle> {
+        self.into_puzzle_from(IN_MEMORY_SOURCE)
+    }
+
+    /// Validates the clues and builds the puzzle they describe. `source_name` is only used in error messages.
+    pub fn into_puzzle_from(self, source_name: &str) -> Result<Puzzle> {
+        self.builder(source_name)?.build_from(source_name)
+    }
+
+    /// A builder for the puzzle, with its clues, metadata and givens. `source_name` is only used in error messages.
+    pub(crate) fn builder(self, source_name: &str) -> Result<PuzzleBuilder> {
+        let givens = match &self.givens {
+            Some(givens) => Some(parse_grid(givens, self.rows.len(), self.columns.len(), "givens", source_name)?),
+            None => None,
+        };
+        let solution = match &self.solution {
+            Some(solution) => Some(parse_grid(
+                solution,
+                self.rows.len(),
+                self.columns.len(),
+                "solution",
+                source_name,
+            )?),
+            None => None,
+        };
+        let mut builder = PuzzleBuilder::new(self.rows, self.columns).title(self.title);
+        if let Some(author) = self.by {
+            builder = builder.author(author);
         }
-        log::debug!("Generating col lines...");
-        let mut cols = Vec::new();
-        for i in 0..col_count {
-            let line = Line::new(
-                LineType::Col,
-                i,
-                vec![Cell::Unknown; row_count as usize],
-                col_clues[i as usize].clone(),
-            );
-            log::debug!(
-                "Generated {} which has {} potential solutions",
-                line.label(),
-                line.potential_solutions.len()
-            );
-            cols.push(line)
+        if let Some(license) = self.license {
+            builder = builder.license(license);
         }
-        log::debug!("Row & col lines generated OK");
-        let mut largest_col_clue_num = 0;
-        for col_clue in &deserialized_puzzle.columns {
-            for clue in col_clue {
-                if *clue > largest_col_clue_num {
-                    largest_col_clue_num = *clue;
-                }
+        if let Some(source) = self.source {
+            builder = builder.source(source);
+        }
+        if let Some(givens) = givens {
+            builder = builder.givens(givens);
+        }
+        if let Some(solution) = solution {
+            builder = builder.solution(solution);
+        }
+        Ok(builder)
+    }
+}
+
+impl From<&Puzzle> for PuzzleConfig {
+    fn from(puzzle: &Puzzle) -> Self {
+        let optional = |value: &String| (!value.is_empty()).then(|| value.clone());
+        PuzzleConfig {
+            title: puzzle.title.clone(),
+            by: optional(&puzzle.author),
+            license: optional(&puzzle.license),
+            source: optional(&puzzle.source),
+            rows: puzzle.row_clues.clone(),
+            columns: puzzle.col_clues.clone(),
+            givens: puzzle.givens.as_deref().map(grid_to_text),
+            solution: puzzle.solution.as_deref().map(grid_to_text),
+        }
+    }
+}
+
+fn grid_to_text(grid: &[Vec<Cell>]) -> Vec<String> {
+    grid.iter().map(|row| cells_to_text(row)).collect()
+}
+
+fn source_name(file_path: &str) -> &str {
+    if file_path == "-" { STDIN_SOURCE } else { file_path }
+}
+
+/// Writes the YAML puzzle format in the same layout as the files in `data`, with one clue per line.
+pub(crate) fn write_yaml(puzzle_config: &PuzzleConfig) -> String {
+    let mut yaml = format!("title: {}\n", quote_yaml(&puzzle_config.title));
+    for (key, value) in [
+        ("by", &puzzle_config.by),
+        ("license", &puzzle_config.license),
+        ("source", &puzzle_config.source),
+    ] {
+        if let Some(value) = value {
+            yaml += &format!("{key}: {}\n", quote_yaml(value));
+        }
+    }
+    for (key, clues) in [("rows", &puzzle_config.rows), ("columns", &puzzle_config.columns)] {
+        yaml += &format!("{key}:\n");
+        for clue in clues {
+            let numbers: Vec<String> = clue.iter().map(|n| n.to_string()).collect();
+            yaml += &format!("  - [{}]\n", numbers.join(","));
+        }
+    }
+    if let Some(givens) = &puzzle_config.givens {
+        yaml += &write_yaml_grid("givens", givens);
+    }
+    if let Some(solution) = &puzzle_config.solution {
+        yaml += &write_yaml_grid("solution", solution);
+    }
+    yaml
+}
+
+/// Writes a grid, such as the givens, with one row per line.
+pub(crate) fn write_yaml_grid(key: &str, grid: &[String]) -> String {
+    let mut yaml = format!("{key}:\n");
+    for row in grid {
+        yaml += &format!("  - \"{row}\"\n");
+    }
+    yaml
+}
+
+/// Parses a grid written as one string per row, using `#` for blocks, `.` for spaces and `?` for unknown cells.
+/// `name` is the grid's field name, and `source_name` the file it came from, for use in error messages.
+pub(crate) fn parse_grid(
+    grid: &[String],
+    row_count: usize,
+    col_count: usize,
+    name: &str,
+    source_name: &str,
+) -> Result<Vec<Vec<Cell>>> {
+    if grid.len() != row_count {
+        return Err(PuzzleError::validation(
+            source_name,
+            format!("the {name} has {} rows, but the puzzle has {row_count}", grid.len()),
+        ));
+    }
+    let mut cells = Vec::new();
+    for (row, text) in grid.iter().enumerate() {
+        match cells_from_text(text) {
+            Some(row_cells) if row_cells.len() == col_count => cells.push(row_cells),
+            Some(row_cells) => {
+                return Err(PuzzleError::validation(
+                    source_name,
+                    format!(
+                        "Row {} of the {name} has {} cells, but the puzzle has {col_count} columns",
+                        row + 1,
+                        row_cells.len()
+                    ),
+                ));
+            }
+            None => {
+                return Err(PuzzleError::validation(
+                    source_name,
+                    format!("Row {} of the {name}, '{text}', may only contain '#', '.' and '?'", row + 1),
+                ));
             }
         }
-        let padding = (largest_col_clue_num.to_string().len() + 1) as u8;
-        let author = deserialized_puzzle.by.unwrap_or_default();
-        let license = deserialized_puzzle.license.unwrap_or_default();
-        let source = deserialized_puzzle.source.unwrap_or_default();
-        let new_puzzle = Puzzle {
-            title: deserialized_puzzle.title,
-            author,
-            license,
-            source,
-            row_clues: deserialized_puzzle.rows.clone(),
-            col_clues: deserialized_puzzle.columns.clone(),
-            padding,
-            rows,
-            cols,
-        };
-        Ok(new_puzzle)
     }
+    Ok(cells)
+}
+
+fn quote_yaml(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\t' => quoted += "\\t",
+            c if c.is_control() => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
 }
@@ -0,0 +1,525 @@
+use crate::cell::Cell;
+use crate::decision_order::CellDecision;
+use crate::line::{Line, LineType};
+use crate::line_algorithms::{clue_from_cells, placed_clue_numbers};
+use crate::puzzle::Puzzle;
+use colored::{Color, Colorize};
+use std::io::IsTerminal;
+
+/// The glyphs used to draw cells in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// `◼` for blocks, `∙` for spaces and `?` for unknown cells.
+    #[default]
+    Unicode,
+    /// `#` for blocks, `.` for spaces and `?` for unknown cells, for terminals and logs without Unicode.
+    Ascii,
+    /// Blocks two characters wide, which look square in most terminals.
+    DoubleWidth,
+}
+
+impl Theme {
+    pub fn glyph(&self, cell: &Cell) -> String {
+        match self {
+            Theme::Unicode => cell.display().trim_end().to_string(),
+            Theme::Ascii => cell.to_char().to_string(),
+            Theme::DoubleWidth => String::from(match cell {
+                Cell::Block => "██",
+                Cell::Space => "··",
+                Cell::Unknown => "░░",
+            }),
+        }
+    }
+}
+
+/// Whether to colour terminal output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Colour only when writing to a terminal, and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output to standard output should be coloured.
+    pub fn use_color(&self) -> bool {
+        self.use_color_for(&std::io::stdout())
+    }
+
+    /// Whether output to the given stream, such as standard error, should be coloured.
+    pub fn use_color_for(&self, stream: &impl IsTerminal) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                // See https://no-color.org: any non-empty value turns colour off.
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_color && stream.is_terminal()
+            }
+        }
+    }
+}
+
+/// How to draw a puzzle in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub theme: Theme,
+    pub color: bool,
+    /// Whether to draw only a compact preview of the cells, without clues, instead of the full grid.
+    pub compact: bool,
+}
+
+impl RenderOptions {
+    pub fn new(theme: Theme, color_choice: ColorChoice) -> RenderOptions {
+        RenderOptions {
+            theme,
+            color: color_choice.use_color(),
+            compact: false,
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions::new(Theme::default(), ColorChoice::default())
+    }
+}
+
+// Lines are drawn between every this many rows and columns, to make counting cells easier.
+const GUIDE_LINE_SPACING: usize = 5;
+
+/// The characters used to draw the rulers and guide lines.
+struct GuideLines {
+    vertical: &'static str,
+    horizontal: &'static str,
+    crossing: &'static str,
+}
+
+impl Theme {
+    fn guide_lines(&self) -> GuideLines {
+        match self {
+            Theme::Ascii => GuideLines {
+                vertical: "|",
+                horizontal: "-",
+                crossing: "+",
+            },
+            Theme::Unicode | Theme::DoubleWidth => GuideLines {
+                vertical: "│",
+                horizontal: "─",
+                crossing: "┼",
+            },
+        }
+    }
+
+    /// Shades for graded cells, from light to dark, each covering an equal part of the range of grades.
+    fn shades(&self) -> [char; SHADE_COUNT] {
+        match self {
+            Theme::Ascii => [':', '=', '*', '@'],
+            Theme::Unicode | Theme::DoubleWidth => ['░', '▒', '▓', '█'],
+        }
+    }
+}
+
+impl Puzzle {
+    /// Draws the puzzle as text: the title and author, then the grid with its clues.
+    /// With the `compact` option, only the title, author and `render_compact`'s preview are drawn.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let mut text = format!("\nTitle: {}\n", self.title);
+        if !self.author.is_empty() {
+            text += &format!("Author: {}\n", self.author);
+        }
+        if options.compact {
+            text += &self.render_compact(options);
+        } else {
+            text += &self.render_grid(options);
+        }
+        text
+    }
+
+    /// Draws a compact preview of the cells, two rows to a line, using half blocks. Unknown cells are grey, or
+    /// shaded without colour, so partly solved puzzles can be previewed too. This is small enough to use as a
+    /// thumbnail.
+    pub fn render_compact(&self, options: &RenderOptions) -> String {
+        let grid = self.grid();
+        let mut text = String::new();
+        for rows in grid.chunks(2) {
+            let mut line = String::new();
+            for (col, top) in rows[0].iter().enumerate() {
+                let bottom = rows.get(1).map_or(&Cell::Space, |row| &row[col]);
+                line += &half_block(top, bottom, options);
+            }
+            text += &line;
+            text += "\n";
+        }
+        text
+    }
+
+    /// Draws the full grid, with the column clues and column numbers above it, and each row's number and right
+    /// aligned clue to the left of its cells. Guide lines are drawn every five rows and columns.
+    fn render_grid(&self, options: &RenderOptions) -> String {
+        let guide_lines = options.theme.guide_lines();
+        let glyph_width = [Cell::Block, Cell::Space, Cell::Unknown]
+            .iter()
+            .map(|cell| options.theme.glyph(cell).chars().count())
+            .max()
+            .unwrap_or(1);
+        let largest_col_clue_number = self.col_clues.iter().flatten().max().copied().unwrap_or(0);
+        // Each column is wide enough for its clue numbers, its ruler number and a cell, plus a space between columns.
+        let cell_width = digits(largest_col_clue_number as usize)
+            .max(digits(self.col_clues.len()))
+            .max(glyph_width)
+            + 1;
+        let row_clues: Vec<Vec<ClueNumber>> = self.rows().iter().map(ClueNumber::for_line).collect();
+        let col_clues: Vec<Vec<ClueNumber>> = self.cols().iter().map(ClueNumber::for_line).collect();
+        let row_clue_width = row_clues.iter().map(|clue| clue_width(clue)).max().unwrap_or(0);
+        let row_number_width = digits(self.row_clues.len());
+        let gutter = " ".repeat(row_number_width + 1 + row_clue_width + 2);
+
+        let mut text = String::new();
+        // Column clues are stacked upwards from the grid, so that their last numbers line up.
+        let max_col_clue_length = col_clues.iter().map(|clue| clue.len()).max().unwrap_or(0);
+        for i in 0..max_col_clue_length {
+            let numbers = col_clues.iter().map(|clue| {
+                let offset = max_col_clue_length - clue.len();
+                if i >= offset {
+                    clue[i - offset].paint(&format!("{:>cell_width$}", clue[i - offset].number), options)
+                } else {
+                    String::new()
+                }
+            });
+            text += &gutter;
+            text += &grid_row(numbers, cell_width, " ");
+            text += "\n";
+        }
+        let col_numbers = (1..=self.col_clues.len()).map(|col| col.to_string());
+        text += &gutter;
+        text += &paint(&grid_row(col_numbers, cell_width, " "), options, None);
+        text += "\n";
+        let horizontal_rule = horizontal_rule(gutter.len(), self.col_clues.len(), cell_width, options);
+        for (row, cells) in self.grid().iter().enumerate() {
+            if row.is_multiple_of(GUIDE_LINE_SPACING) {
+                text += &horizontal_rule;
+                text += "\n";
+            }
+            text += &paint(&format!("{:>row_number_width$} ", row + 1), options, None);
+            text += &" ".repeat(row_clue_width - clue_width(&row_clues[row]));
+            let numbers: Vec<String> = row_clues[row]
+                .iter()
+                .map(|number| number.paint(&number.number.to_string(), options))
+                .collect();
+            text += &numbers.join(", ");
+            text += " ";
+            text += &paint(guide_lines.vertical, options, None);
+            let glyphs = cells.iter().map(|cell| options.theme.glyph(cell));
+            text += &paint(&grid_row(glyphs, cell_width, guide_lines.vertical), options, Some(Color::Blue));
+            text += "\n";
+        }
+        text
+    }
+
+    /// Draws a heat map of how likely each unknown cell is to be a block, from `block_probabilities`: the more
+    /// likely, the darker the cell. Cells are shaded by the mean of their row and column fractions, which the CSV
+    /// export gives separately. Known cells are drawn as usual, but dimmed.
+    pub fn render_heat_map(&self, options: &RenderOptions) -> String {
+        let probabilities = self.block_probabilities();
+        let grades: Vec<Vec<Option<f64>>> = self
+            .grid()
+            .iter()
+            .zip(probabilities)
+            .map(|(cells, row)| {
+                cells
+                    .iter()
+                    .zip(row)
+                    .map(|(cell, probability)| (*cell == Cell::Unknown).then_some(probability.combined()))
+                    .collect()
+            })
+            .collect();
+        let mut text = format!("\nTitle: {}\n", self.title);
+        text += "Chance of each unknown cell being a block, averaged over its row and column: ";
+        text += &shade_legend(&["< 25%", "< 50%", "< 75%", ">= 75%"].map(String::from), options);
+        text += "\n";
+        text += &self.render_graded_grid(&grades, options);
+        text
+    }
+
+    /// Draws the order in which the solver decided the cells, from a `DecisionRecorder`: cells decided in early
+    /// iterations are light, and those decided late are dark. Cells which were known before solving started, or
+    /// are still unknown, are drawn as usual, but dimmed.
+    pub fn render_decision_order(&self, decisions: &[Vec<Option<CellDecision>>], options: &RenderOptions) -> String {
+        let last_iteration = decisions.iter().flatten().flatten().map(|decision| decision.iteration).max();
+        let grade = |iteration: u32| match last_iteration {
+            Some(last_iteration) if last_iteration > 1 => (iteration - 1) as f64 / (last_iteration - 1) as f64,
+            _ => 0.0,
+        };
+        let grades: Vec<Vec<Option<f64>>> = decisions
+            .iter()
+            .map(|row| row.iter().map(|decision| decision.map(|decision| grade(decision.iteration))).collect())
+            .collect();
+        // Label each shade with the first and last iterations it covers.
+        let mut ranges = [None; SHADE_COUNT];
+        for iteration in 1..=last_iteration.unwrap_or(0) {
+            let range = &mut ranges[shade_index(grade(iteration))];
+            *range = Some(range.map_or((iteration, iteration), |(first, _)| (first, iteration)));
+        }
+        let labels = ranges.map(|range| match range {
+            Some((first, last)) if first == last => first.to_string(),
+            Some((first, last)) => format!("{first}-{last}"),
+            None => String::new(),
+        });
+        let count = |axis: Option<LineType>| decisions.iter().flatten().flatten().filter(|d| d.axis == axis).count();
+
+        let mut text = format!("\nTitle: {}\n", self.title);
+        text += "Iteration in which each cell was decided: ";
+        text += &shade_legend(&labels, options);
+        text += "\n";
+        text += &self.render_graded_grid(&grades, options);
+        text += &format!(
+            "Cells decided by rows: {}, by columns: {}, by probing: {}\n",
+            count(Some(LineType::Row)),
+            count(Some(LineType::Col)),
+            count(None)
+        );
+        text
+    }
+
+    /// Draws the grid with a ruler, shading each cell which has a grade from light, for 0, to dark, for 1. Cells
+    /// without a grade are drawn as usual, but dimmed.
+    fn render_graded_grid(&self, grades: &[Vec<Option<f64>>], options: &RenderOptions) -> String {
+        let guide_lines = options.theme.guide_lines();
+        let glyph_width = options.theme.glyph(&Cell::Block).chars().count();
+        let cell_width = digits(self.cols().len()).max(glyph_width) + 1;
+        let row_number_width = digits(self.rows().len());
+        let gutter = " ".repeat(row_number_width + 2);
+
+        let mut text = gutter.clone();
+        let col_numbers = (1..=self.cols().len()).map(|col| col.to_string());
+        text += &paint(&grid_row(col_numbers, cell_width, " "), options, None);
+        text += "\n";
+        let horizontal_rule = horizontal_rule(gutter.len(), self.cols().len(), cell_width, options);
+        for (row, (cells, grades)) in self.grid().iter().zip(grades).enumerate() {
+            if row.is_multiple_of(GUIDE_LINE_SPACING) {
+                text += &horizontal_rule;
+                text += "\n";
+            }
+            text += &paint(&format!("{:>row_number_width$} ", row + 1), options, None);
+            text += &paint(guide_lines.vertical, options, None);
+            let glyphs = cells.iter().zip(grades).map(|(cell, grade)| match grade {
+                Some(grade) => {
+                    let shade = options.theme.shades()[shade_index(*grade)];
+                    paint_graded(&format!("{:>cell_width$}", shade.to_string().repeat(glyph_width)), *grade, options)
+                }
+                None => paint(&format!("{:>cell_width$}", options.theme.glyph(cell)), options, None),
+            });
+            text += &grid_row(glyphs, cell_width, &paint(guide_lines.vertical, options, None));
+            text += "\n";
+        }
+        text
+    }
+}
+
+const SHADE_COUNT: usize = 4;
+
+/// The shade for a grade between 0 and 1.
+fn shade_index(grade: f64) -> usize {
+    ((grade * SHADE_COUNT as f64) as usize).min(SHADE_COUNT - 1)
+}
+
+/// The key to the shades of a graded grid, with a label for each shade. Shades without a label are left out.
+fn shade_legend(labels: &[String; SHADE_COUNT], options: &RenderOptions) -> String {
+    let keys: Vec<String> = options
+        .theme
+        .shades()
+        .iter()
+        .zip(labels)
+        .enumerate()
+        .filter(|(_, (_, label))| !label.is_empty())
+        .map(|(i, (shade, label))| {
+            let grade = (i as f64 + 0.5) / SHADE_COUNT as f64;
+            format!("{} {label}", paint_graded(&shade.to_string(), grade, options))
+        })
+        .collect();
+    keys.join("  ")
+}
+
+/// Colours graded text from pale yellow, for 0, to dark red, for 1, if colour is on.
+fn paint_graded(text: &str, grade: f64, options: &RenderOptions) -> String {
+    if !options.color {
+        return text.to_string();
+    }
+    let blend = |light: f64, dark: f64| (light + (dark - light) * grade.clamp(0.0, 1.0)).round() as u8;
+    text.color(Color::TrueColor {
+        r: blend(255.0, 140.0),
+        g: blend(240.0, 0.0),
+        b: blend(160.0, 0.0),
+    })
+    .to_string()
+}
+
+/// A dimmed guide line across the grid, below a gutter of the given width.
+fn horizontal_rule(gutter_width: usize, col_count: usize, cell_width: usize, options: &RenderOptions) -> String {
+    let guide_lines = options.theme.guide_lines();
+    let rule = format!(
+        "{}{}{}",
+        " ".repeat(gutter_width - 1),
+        guide_lines.crossing,
+        grid_row(
+            (0..col_count).map(|_| guide_lines.horizontal.repeat(cell_width)),
+            cell_width,
+            guide_lines.crossing
+        )
+    );
+    paint(&rule, options, None)
+}
+
+/// One number of a line's clue, and how far the line has got with it.
+struct ClueNumber {
+    number: u8,
+    /// Whether the run of blocks for this number has been found in the line.
+    placed: bool,
+    /// Whether every cell of the line is known and fits the clue.
+    line_complete: bool,
+}
+
+impl ClueNumber {
+    fn for_line(line: &Line) -> Vec<ClueNumber> {
+        let placed = placed_clue_numbers(&line.clue, &line.cells);
+        let line_complete = !line.cells.contains(&Cell::Unknown) && clue_from_cells(&line.cells) == line.clue;
+        line.clue
+            .iter()
+            .zip(placed)
+            .map(|(&number, placed)| ClueNumber {
+                number,
+                placed,
+                line_complete,
+            })
+            .collect()
+    }
+
+    /// Greys out the numbers of a complete line, and dims numbers which have been placed.
+    fn paint(&self, text: &str, options: &RenderOptions) -> String {
+        if !options.color {
+            text.to_string()
+        } else if self.line_complete {
+            text.bright_black().to_string()
+        } else if self.placed {
+            text.yellow().dimmed().to_string()
+        } else {
+            text.yellow().to_string()
+        }
+    }
+}
+
+/// The width of a clue written out with its numbers separated by commas.
+fn clue_width(clue: &[ClueNumber]) -> usize {
+    clue.iter().map(|number| digits(number.number as usize)).sum::<usize>() + 2 * clue.len().saturating_sub(1)
+}
+
+/// Lays out one item per column, right aligned, with a separator between every group of five columns. Items which
+/// are painted must already be padded to the cell width, as the colour codes would otherwise count towards it.
+fn grid_row(items: impl Iterator<Item = String>, cell_width: usize, separator: &str) -> String {
+    let mut row = String::new();
+    for (col, item) in items.enumerate() {
+        if col > 0 && col.is_multiple_of(GUIDE_LINE_SPACING) {
+            row += separator;
+        }
+        row += &format!("{item:>cell_width$}");
+    }
+    row
+}
+
+/// The half block showing two vertically adjacent cells. Blocks are always drawn in their half, and unknown cells
+/// are grey. Without colour, an unknown cell beside a block can't be told apart from a space, and any other pair
+/// with an unknown cell is shaded.
+fn half_block(top: &Cell, bottom: &Cell, options: &RenderOptions) -> String {
+    let halves = |top: bool, bottom: bool| match (top, bottom) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    };
+    let blocks = halves(*top == Cell::Block, *bottom == Cell::Block);
+    let unknown = halves(*top == Cell::Unknown, *bottom == Cell::Unknown);
+    match (unknown, blocks, options.color) {
+        (' ', _, _) => blocks.to_string(),
+        (_, ' ', false) => String::from('░'),
+        (_, ' ', true) => unknown.to_string().bright_black().to_string(),
+        (_, _, false) => blocks.to_string(),
+        // The block is drawn in the foreground, leaving the unknown half to the background.
+        (_, _, true) => blocks.to_string().on_bright_black().to_string(),
+    }
+}
+
+fn digits(number: usize) -> usize {
+    number.to_string().len()
+}
+
+/// Colours text if colour is on, or dims it if no colour is given.
+fn paint(text: &str, options: &RenderOptions, colour: Option<Color>) -> String {
+    match (options.color, colour) {
+        (false, _) => text.to_string(),
+        (true, Some(colour)) => text.color(colour).to_string(),
+        (true, None) => text.dimmed().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::cells_from_text;
+    use crate::line_algorithms::clues_from_grid;
+    use crate::puzzle_builder::PuzzleBuilder;
+
+    #[test]
+    fn half_block_keeps_the_block_beside_an_unknown_cell() {
+        let options = RenderOptions {
+            theme: Theme::Unicode,
+            color: false,
+            compact: true,
+        };
+        assert_eq!(half_block(&Cell::Block, &Cell::Unknown, &options), "▀");
+        assert_eq!(half_block(&Cell::Unknown, &Cell::Block, &options), "▄");
+        assert_eq!(half_block(&Cell::Unknown, &Cell::Space, &options), "░");
+        assert_eq!(half_block(&Cell::Unknown, &Cell::Unknown, &options), "░");
+        assert_eq!(half_block(&Cell::Space, &Cell::Block, &options), "▄");
+    }
+
+    #[test]
+    fn double_width_rulers_and_guide_lines_line_up_with_the_cells() {
+        let text_grid = |rows: [&str; 6]| -> Vec<Vec<Cell>> {
+            rows.iter().map(|row| cells_from_text(row).unwrap()).collect()
+        };
+        let (row_clues, col_clues) =
+            clues_from_grid(&text_grid(["##.###", "#.....", "#.####", "......", "###.#.", "#....#"]));
+        let puzzle = PuzzleBuilder::new(row_clues, col_clues)
+            .title("Guides")
+            .starting_grid(&text_grid(["##.###", "?.?...", "#.####", "......", "###.#.", "#?...#"]))
+            .build()
+            .unwrap();
+        let options = RenderOptions {
+            theme: Theme::DoubleWidth,
+            color: false,
+            compact: false,
+        };
+        let expected = [
+            "",
+            "Title: Guides",
+            "                      1   1",
+            "          3  1  1  1  1   1",
+            "          2  1  1  1  1   1",
+            "          1  2  3  4  5   6",
+            "       ┼───────────────┼───",
+            "1 2, 3 │ ██ ██ ·· ██ ██│ ██",
+            "2    1 │ ░░ ·· ░░ ·· ··│ ··",
+            "3 1, 4 │ ██ ·· ██ ██ ██│ ██",
+            "4      │ ·· ·· ·· ·· ··│ ··",
+            "5 3, 1 │ ██ ██ ██ ·· ██│ ··",
+            "       ┼───────────────┼───",
+            "6 1, 1 │ ██ ░░ ·· ·· ··│ ██",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(puzzle.render(&options), expected);
+    }
+}
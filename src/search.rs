@@ -0,0 +1,99 @@
+use crate::cell::Cell;
+use crate::observer::NullObserver;
+use crate::puzzle::{Puzzle, SolveOptions};
+
+impl Puzzle {
+    /// Finds up to `limit` complete solutions by guessing the value of an unknown cell whenever line logic
+    /// stalls, and backtracking when a guess leads to a contradiction. This puzzle is left untouched.
+    ///
+    /// Also returns whether the search was exhaustive: when it is cut short by the cancellation token or
+    /// `max_iterations`, there may be solutions which were not found.
+    pub fn find_solutions(&self, limit: usize, options: &SolveOptions) -> (Vec<Puzzle>, bool) {
+        let search_options = SolveOptions {
+            threads: 1,
+            probing: false,
+            ..options.clone()
+        };
+        let mut solutions = Vec::new();
+        let mut exhaustive = true;
+        search(self.clone(), limit, &search_options, &mut solutions, &mut exhaustive);
+        (solutions, exhaustive)
+    }
+
+    fn first_unknown_cell(&self) -> Option<(u8, u8)> {
+        for row in 0..self.row_count() {
+            for col in 0..self.col_count() {
+                if self.cell(row, col) == Cell::Unknown {
+                    return Some((row, col));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn search(
+    mut puzzle: Puzzle,
+    limit: usize,
+    options: &SolveOptions,
+    solutions: &mut Vec<Puzzle>,
+    exhaustive: &mut bool,
+) {
+    if solutions.len() >= limit || !*exhaustive {
+        return;
+    }
+    let stats = puzzle.solve_with_observer(options, &mut NullObserver);
+    if stats.contradiction.is_some() {
+        return;
+    }
+    if stats.solved {
+        solutions.push(puzzle);
+        return;
+    }
+    if stats.cancelled || stats.limit_reached {
+        *exhaustive = false;
+        return;
+    }
+    let Some((row, col)) = puzzle.first_unknown_cell() else {
+        return;
+    };
+    for guess in [Cell::Block, Cell::Space] {
+        let mut guessed = puzzle.clone();
+        guessed.set_cell(row, col, guess);
+        search(guessed, limit, options, solutions, exhaustive);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::cells_from_text;
+    use crate::puzzle_builder::PuzzleBuilder;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<Cell>> {
+        rows.iter().map(|row| cells_from_text(row).unwrap()).collect()
+    }
+
+    fn unknown_cells(puzzle: &Puzzle) -> usize {
+        puzzle.grid().iter().flatten().filter(|cell| **cell == Cell::Unknown).count()
+    }
+
+    #[test]
+    fn finds_both_solutions_of_an_ambiguous_puzzle() {
+        let puzzle = PuzzleBuilder::new(vec![vec![1], vec![1]], vec![vec![1], vec![1]]).build().unwrap();
+        let (solutions, exhaustive) = puzzle.find_solutions(2, &SolveOptions::default());
+        assert!(exhaustive);
+        let grids: Vec<Vec<Vec<Cell>>> = solutions.iter().map(|solution| solution.grid()).collect();
+        assert_eq!(grids, vec![grid(&["#.", ".#"]), grid(&[".#", "#."])]);
+        assert_eq!(unknown_cells(&puzzle), 4, "the puzzle itself is left untouched");
+    }
+
+    #[test]
+    fn finds_the_only_solution_of_a_unique_puzzle() {
+        let puzzle = PuzzleBuilder::new(vec![vec![2], vec![1]], vec![vec![2], vec![1]]).build().unwrap();
+        let (solutions, exhaustive) = puzzle.find_solutions(2, &SolveOptions::default());
+        assert!(exhaustive);
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].grid(), grid(&["##", "#."]));
+    }
+}
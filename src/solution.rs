@@ -0,0 +1,38 @@
+use crate::cell::Cell;
+use crate::puzzle::Puzzle;
+use serde::Serialize;
+
+/// A cell whose value differs from the puzzle's known solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CellMismatch {
+    pub row: u8,
+    pub col: u8,
+    pub expected: Cell,
+    pub actual: Cell,
+}
+
+impl Puzzle {
+    /// Compares the puzzle's known cells with its known solution, cell by cell. Cells which are still
+    /// unknown aren't mismatches, so a partially solved puzzle can be checked too. Puzzles without a
+    /// known solution have no mismatches.
+    pub fn solution_mismatches(&self) -> Vec<CellMismatch> {
+        let Some(solution) = &self.solution else {
+            return Vec::new();
+        };
+        let mut mismatches = Vec::new();
+        for (row, expected_cells) in solution.iter().enumerate() {
+            for (col, expected) in expected_cells.iter().enumerate() {
+                let actual = self.cell(row as u8, col as u8);
+                if actual != Cell::Unknown && actual != *expected {
+                    mismatches.push(CellMismatch {
+                        row: row as u8,
+                        col: col as u8,
+                        expected: expected.clone(),
+                        actual,
+                    });
+                }
+            }
+        }
+        mismatches
+    }
+}
@@ -0,0 +1,76 @@
+use crate::cell::{Cell, cells_to_text};
+use crate::error::{PuzzleError, Result};
+use crate::puzzle::Puzzle;
+use crate::puzzle_factory::{PuzzleConfig, parse_grid, write_yaml, write_yaml_grid};
+use serde::{Deserialize, Serialize};
+
+/// A partially solved puzzle, or a game in progress, which can be saved and resumed later.
+///
+/// The state is saved as YAML: the puzzle file's own fields, followed by the iteration count and the grid,
+/// one string per row using `#` for blocks, `.` for spaces and `?` for unknown cells. Each line's remaining
+/// potential solutions aren't saved, since they are exactly those which fit the known cells of the grid,
+/// and are rebuilt when the state is resumed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SolverState {
+    #[serde(flatten)]
+    pub puzzle: PuzzleConfig,
+    /// Line logic iterations used before the state was saved.
+    #[serde(default)]
+    pub iterations: u32,
+    pub grid: Vec<String>,
+}
+
+impl SolverState {
+    pub fn new(puzzle: &Puzzle, iterations: u32) -> SolverState {
+        SolverState {
+            puzzle: PuzzleConfig::from(puzzle),
+            iterations,
+            grid: puzzle.grid().iter().map(|row| cells_to_text(row)).collect(),
+        }
+    }
+
+    pub fn load(file_path: &str) -> Result<SolverState> {
+        let text = std::fs::read_to_string(file_path).map_err(|err| PuzzleError::Io {
+            path: file_path.to_string(),
+            source: err,
+        })?;
+        serde_yaml::from_str(&text).map_err(|err| PuzzleError::from_yaml(file_path, err))
+    }
+
+    pub fn save(&self, file_path: &str) -> Result<()> {
+        std::fs::write(file_path, self.to_yaml()).map_err(|err| PuzzleError::Io {
+            path: file_path.to_string(),
+            source: err,
+        })
+    }
+
+    /// Writes the state in the same layout as the puzzle files in `data`, with one clue or grid row per line.
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = write_yaml(&self.puzzle);
+        yaml += &format!("iterations: {}\n", self.iterations);
+        yaml += &write_yaml_grid("grid", &self.grid);
+        yaml
+    }
+
+    /// The saved grid, one row at a time. `file_path` is the file the state was loaded from, and is only
+    /// used in error messages.
+    pub fn cells(&self, file_path: &str) -> Result<Vec<Vec<Cell>>> {
+        parse_grid(
+            &self.grid,
+            self.puzzle.rows.len(),
+            self.puzzle.columns.len(),
+            "grid",
+            file_path,
+        )
+    }
+
+    /// Rebuilds the puzzle with the saved grid, ready for solving to carry on where it stopped. `file_path`
+    /// is the file the state was loaded from, and is only used in error messages.
+    pub fn into_puzzle(self, file_path: &str) -> Result<Puzzle> {
+        let grid = self.cells(file_path)?;
+        self.puzzle
+            .builder(file_path)?
+            .starting_grid(&grid)
+            .build_from(file_path)
+    }
+}
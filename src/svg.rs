@@ -0,0 +1,177 @@
+use crate::cell::Cell;
+use crate::puzzle::Puzzle;
+
+// Lines are drawn thicker every this many cells, to make counting cells easier.
+pub(crate) const THICK_LINE_SPACING: usize = 5;
+const THIN_LINE_COLOUR: &str = "#999999";
+const THICK_LINE_COLOUR: &str = "#000000";
+const BLOCK_COLOUR: &str = "#000000";
+const CLUE_GUTTER_COLOUR: &str = "#eeeeee";
+
+/// Options for drawing a puzzle as SVG.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// The width and height of each cell, in pixels.
+    pub cell_size: u32,
+    /// Whether to draw the puzzle's known cells, or else an empty grid to solve on paper.
+    pub show_cells: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            cell_size: 20,
+            show_cells: false,
+        }
+    }
+}
+
+impl Puzzle {
+    /// Draws the puzzle as a standalone SVG document, for printing: the title and author at the top, the
+    /// column clues above the grid and the row clues to its left, and the license and source in a footer.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let cell = options.cell_size as usize;
+        let margin = cell;
+        let row_count = self.row_count() as usize;
+        let col_count = self.col_count() as usize;
+        let row_gutter = self.row_clues.iter().map(|clue| clue.len()).max().unwrap_or(0).max(1) * cell;
+        let col_gutter = self.col_clues.iter().map(|clue| clue.len()).max().unwrap_or(0).max(1) * cell;
+        let mut header_lines = vec![(self.title.as_str(), cell * 6 / 5, "bold")];
+        if !self.author.is_empty() {
+            header_lines.push((self.author.as_str(), cell * 4 / 5, "normal"));
+        }
+        let mut footer_lines = Vec::new();
+        for text in [&self.license, &self.source] {
+            if !text.is_empty() {
+                footer_lines.push(text.as_str());
+            }
+        }
+        let header = header_lines.iter().map(|(_, size, _)| size * 3 / 2).sum::<usize>();
+        let footer_line = cell * 3 / 5;
+        let grid_left = margin + row_gutter;
+        let grid_top = margin + header + col_gutter;
+        let grid_width = col_count * cell;
+        let grid_height = row_count * cell;
+        // Wide enough for the header and footer too, estimating each character as 0.6 of the font size.
+        let text_width = header_lines
+            .iter()
+            .map(|(text, size, _)| (text.chars().count(), *size))
+            .chain(footer_lines.iter().map(|text| (text.chars().count(), footer_line)))
+            .map(|(length, size)| length * size * 3 / 5)
+            .max()
+            .unwrap_or(0);
+        let width = (grid_left + grid_width).max(margin + text_width) + margin;
+        let height = grid_top + grid_height + margin + footer_lines.len() * footer_line * 3 / 2;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\">\n"
+        );
+        svg += &format!("<rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>\n");
+
+        let mut y = margin;
+        for (text, size, weight) in &header_lines {
+            y += size;
+            svg += &format!(
+                "<text x=\"{margin}\" y=\"{y}\" font-size=\"{size}\" font-weight=\"{weight}\">{}</text>\n",
+                escape_xml(text)
+            );
+            y += size / 2;
+        }
+
+        svg += &format!(
+            "<rect x=\"{grid_left}\" y=\"{}\" width=\"{grid_width}\" height=\"{col_gutter}\" fill=\"{CLUE_GUTTER_COLOUR}\"/>\n",
+            grid_top - col_gutter
+        );
+        svg += &format!(
+            "<rect x=\"{}\" y=\"{grid_top}\" width=\"{row_gutter}\" height=\"{grid_height}\" fill=\"{CLUE_GUTTER_COLOUR}\"/>\n",
+            grid_left - row_gutter
+        );
+        let font_size = cell * 3 / 5;
+        let baseline = (cell + font_size) / 2 - cell / 10;
+        for (col, clue) in self.col_clues.iter().enumerate() {
+            let clue = printed_clue(clue);
+            // Column clues are stacked upwards from the grid, so that their last numbers line up.
+            for (i, number) in clue.iter().enumerate() {
+                let x = grid_left + col * cell + cell / 2;
+                let y = grid_top - (clue.len() - i) * cell + baseline;
+                svg += &clue_text(x, y, font_size, *number);
+            }
+        }
+        for (row, clue) in self.row_clues.iter().enumerate() {
+            let clue = printed_clue(clue);
+            // Row clues are right aligned against the grid.
+            for (i, number) in clue.iter().enumerate() {
+                let x = grid_left - (clue.len() - i) * cell + cell / 2;
+                let y = grid_top + row * cell + baseline;
+                svg += &clue_text(x, y, font_size, *number);
+            }
+        }
+
+        if options.show_cells {
+            for (row, cells) in self.grid().iter().enumerate() {
+                for (col, cell_value) in cells.iter().enumerate() {
+                    if *cell_value == Cell::Block {
+                        svg += &format!(
+                            "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" fill=\"{BLOCK_COLOUR}\"/>\n",
+                            grid_left + col * cell,
+                            grid_top + row * cell
+                        );
+                    }
+                }
+            }
+        }
+
+        // Thin lines first, so that the thick lines are drawn over them.
+        for thick in [false, true] {
+            for col in 0..=col_count {
+                if is_thick_line(col, col_count) == thick {
+                    let x = grid_left + col * cell;
+                    svg += &grid_line(x, grid_top - col_gutter, x, grid_top + grid_height, thick);
+                }
+            }
+            for row in 0..=row_count {
+                if is_thick_line(row, row_count) == thick {
+                    let y = grid_top + row * cell;
+                    svg += &grid_line(grid_left - row_gutter, y, grid_left + grid_width, y, thick);
+                }
+            }
+        }
+
+        let mut y = grid_top + grid_height + margin / 2;
+        for text in footer_lines {
+            y += footer_line * 3 / 2;
+            svg += &format!(
+                "<text x=\"{margin}\" y=\"{y}\" font-size=\"{footer_line}\" fill=\"#555555\">{}</text>\n",
+                escape_xml(text)
+            );
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// The numbers printed for a clue: an empty clue is printed as 0, so that it isn't mistaken for a missing one.
+pub(crate) fn printed_clue(clue: &[u8]) -> &[u8] {
+    if clue.is_empty() { &[0] } else { clue }
+}
+
+fn is_thick_line(index: usize, count: usize) -> bool {
+    index.is_multiple_of(THICK_LINE_SPACING) || index == count
+}
+
+fn grid_line(x1: usize, y1: usize, x2: usize, y2: usize, thick: bool) -> String {
+    let (colour, width) = if thick { (THICK_LINE_COLOUR, 2) } else { (THIN_LINE_COLOUR, 1) };
+    format!("<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{colour}\" stroke-width=\"{width}\"/>\n")
+}
+
+fn clue_text(x: usize, y: usize, font_size: usize, number: u8) -> String {
+    format!("<text x=\"{x}\" y=\"{y}\" font-size=\"{font_size}\" text-anchor=\"middle\">{number}</text>\n")
+}
+
+/// Escapes text for use in XML or HTML.
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
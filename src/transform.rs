@@ -0,0 +1,165 @@
+use crate::cell::Cell;
+use crate::error::{IN_MEMORY_SOURCE, PuzzleError, Result};
+use crate::line_algorithms::clues_from_grid;
+use crate::puzzle::Puzzle;
+use crate::puzzle_builder::PuzzleBuilder;
+
+/// The parts of a puzzle which move when it is turned or flipped, with every grid held one row at a time.
+struct Layout {
+    row_clues: Vec<Vec<u8>>,
+    col_clues: Vec<Vec<u8>>,
+    cells: Vec<Vec<Cell>>,
+    givens: Option<Vec<Vec<Cell>>>,
+    solution: Option<Vec<Vec<Cell>>>,
+}
+
+impl Layout {
+    fn of(puzzle: &Puzzle) -> Layout {
+        Layout {
+            row_clues: puzzle.row_clues.clone(),
+            col_clues: puzzle.col_clues.clone(),
+            cells: puzzle.grid(),
+            givens: puzzle.givens.clone(),
+            solution: puzzle.solution.clone(),
+        }
+    }
+
+    /// Swaps rows and columns, flipping the puzzle about its top left to bottom right diagonal.
+    fn transpose(self) -> Layout {
+        Layout {
+            row_clues: self.col_clues,
+            col_clues: self.row_clues,
+            cells: transpose_grid(&self.cells),
+            givens: self.givens.as_deref().map(transpose_grid),
+            solution: self.solution.as_deref().map(transpose_grid),
+        }
+    }
+
+    /// Swaps the left and right of the puzzle.
+    fn mirror_horizontal(mut self) -> Layout {
+        for clue in &mut self.row_clues {
+            clue.reverse();
+        }
+        self.col_clues.reverse();
+        for grid in std::iter::once(&mut self.cells).chain(self.givens.as_mut()).chain(self.solution.as_mut()) {
+            for row in grid.iter_mut() {
+                row.reverse();
+            }
+        }
+        self
+    }
+
+    /// Swaps the top and bottom of the puzzle.
+    fn mirror_vertical(mut self) -> Layout {
+        self.row_clues.reverse();
+        for clue in &mut self.col_clues {
+            clue.reverse();
+        }
+        for grid in std::iter::once(&mut self.cells).chain(self.givens.as_mut()).chain(self.solution.as_mut()) {
+            grid.reverse();
+        }
+        self
+    }
+
+    /// Builds the transformed puzzle, keeping the original's metadata, its givens and the cells solved so far.
+    fn into_puzzle(self, original: &Puzzle) -> Result<Puzzle> {
+        let mut builder = PuzzleBuilder::new(self.row_clues, self.col_clues)
+            .title(original.title.clone())
+            .author(original.author.clone())
+            .license(original.license.clone())
+            .source(original.source.clone());
+        if let Some(givens) = self.givens {
+            builder = builder.givens(givens);
+        }
+        builder = builder.starting_grid(&self.cells);
+        if let Some(solution) = self.solution {
+            builder = builder.solution(solution);
+        }
+        builder.build_from(IN_MEMORY_SOURCE)
+    }
+}
+
+// Turning or flipping a valid puzzle gives another valid puzzle, so these only fail if the cells solved so far
+// already contradict the clues.
+impl Puzzle {
+    /// The puzzle with its rows and columns swapped.
+    pub fn transpose(&self) -> Result<Puzzle> {
+        Layout::of(self).transpose().into_puzzle(self)
+    }
+
+    /// The puzzle turned a quarter turn clockwise.
+    pub fn rotate_90(&self) -> Result<Puzzle> {
+        Layout::of(self).transpose().mirror_horizontal().into_puzzle(self)
+    }
+
+    /// The puzzle turned upside down.
+    pub fn rotate_180(&self) -> Result<Puzzle> {
+        Layout::of(self).mirror_horizontal().mirror_vertical().into_puzzle(self)
+    }
+
+    /// The puzzle turned a quarter turn anticlockwise.
+    pub fn rotate_270(&self) -> Result<Puzzle> {
+        Layout::of(self).transpose().mirror_vertical().into_puzzle(self)
+    }
+
+    /// The puzzle with its left and right swapped.
+    pub fn mirror_horizontal(&self) -> Result<Puzzle> {
+        Layout::of(self).mirror_horizontal().into_puzzle(self)
+    }
+
+    /// The puzzle with its top and bottom swapped.
+    pub fn mirror_vertical(&self) -> Result<Puzzle> {
+        Layout::of(self).mirror_vertical().into_puzzle(self)
+    }
+
+    /// The puzzle whose solution has blocks and spaces swapped. Unlike turning or flipping, the new clues
+    /// can't be worked out from the old ones, so this needs the puzzle's known solution, or else a fully
+    /// solved grid. The givens and the cells solved so far are swapped too.
+    pub fn invert(&self) -> Result<Puzzle> {
+        let solution = match &self.solution {
+            Some(solution) => solution.clone(),
+            None if self.is_complete() => self.grid(),
+            None => {
+                return Err(PuzzleError::validation(
+                    IN_MEMORY_SOURCE,
+                    String::from("a puzzle can only be inverted once its solution is known"),
+                ));
+            }
+        };
+        let solution = invert_grid(&solution);
+        let (row_clues, col_clues) = clues_from_grid(&solution);
+        Layout {
+            row_clues,
+            col_clues,
+            cells: invert_grid(&self.grid()),
+            givens: self.givens.as_deref().map(invert_grid),
+            solution: Some(solution),
+        }
+        .into_puzzle(self)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.rows.iter().all(|line| !line.cells.contains(&Cell::Unknown))
+    }
+}
+
+fn transpose_grid(grid: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+    let col_count = grid.first().map_or(0, |row| row.len());
+    (0..col_count)
+        .map(|col| grid.iter().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+fn invert_grid(grid: &[Vec<Cell>]) -> Vec<Vec<Cell>> {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Cell::Block => Cell::Space,
+                    Cell::Space => Cell::Block,
+                    Cell::Unknown => Cell::Unknown,
+                })
+                .collect()
+        })
+        .collect()
+}
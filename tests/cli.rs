@@ -0,0 +1,32 @@
+use nonorust::PuzzleConfig;
+use std::process::Command;
+
+fn nonorust(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_nonorust"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn info_counts_arrangements_of_large_puzzles() {
+    for path in ["data/mt_fuji.nonogram.yaml", "data/wikimedia.nonogram.yaml"] {
+        let output = nonorust(&["info", "--path", path, "--format", "json"]);
+        assert!(
+            output.status.success(),
+            "info failed for {path}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        // A freshly loaded puzzle still holds every arrangement of each line.
+        let puzzle = PuzzleConfig::build(format!("{}/{path}", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let arrangements: usize = puzzle
+            .rows()
+            .iter()
+            .chain(puzzle.cols())
+            .map(|line| line.potential_solution_count())
+            .sum();
+        assert_eq!(info["potential_solutions"].as_u64(), Some(arrangements as u64), "wrong count for {path}");
+    }
+}
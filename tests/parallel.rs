@@ -0,0 +1,43 @@
+use nonorust::{NullObserver, PuzzleConfig, SolveOptions};
+use std::path::Path;
+
+/// Every puzzle file under `data`, in name order.
+fn data_puzzles(dir: &Path, paths: &mut Vec<String>) {
+    let mut entries: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            data_puzzles(&entry, paths);
+        } else if entry.extension().is_some_and(|extension| extension == "yaml") {
+            paths.push(entry.display().to_string());
+        }
+    }
+}
+
+#[test]
+fn parallel_solver_matches_sequential_solver() {
+    let mut paths = Vec::new();
+    data_puzzles(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/data")), &mut paths);
+    assert!(!paths.is_empty());
+    for path in paths {
+        let puzzle = PuzzleConfig::build(path.clone()).unwrap();
+        let mut sequential = puzzle.clone();
+        let mut parallel = puzzle;
+        let sequential_stats = sequential.solve_with_observer(
+            &SolveOptions {
+                threads: 1,
+                ..SolveOptions::default()
+            },
+            &mut NullObserver,
+        );
+        let parallel_stats = parallel.solve_with_observer(
+            &SolveOptions {
+                threads: 4,
+                ..SolveOptions::default()
+            },
+            &mut NullObserver,
+        );
+        assert_eq!(sequential.grid(), parallel.grid(), "grids differ for {path}");
+        assert_eq!(sequential_stats, parallel_stats, "stats differ for {path}");
+    }
+}